@@ -0,0 +1,457 @@
+use crate::prelude::*;
+use jsonrpsee::types::ErrorObject;
+
+/// Code geth and erigon use for reverts that carry revert data.
+pub const EXECUTION_REVERTED_CODE: i32 = 3;
+/// Code geth and erigon use for most transaction and state errors, "Invalid input" in EIP-1474.
+pub const SERVER_ERROR_CODE: i32 = -32000;
+
+/// JSON-RPC error returned by Ethereum nodes, classified by EIP-1474 code and geth/erigon message.
+///
+/// Every variant keeps the node's message, so that converting back into an [`ErrorObject`]
+/// preserves it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EthRpcError {
+    /// Contract execution reverted, with revert data if the node returned it.
+    ExecutionReverted {
+        message: String,
+        data: Option<Bytes>,
+    },
+    NonceTooLow(String),
+    NonceTooHigh(String),
+    /// Sender cannot pay for gas and value.
+    InsufficientFunds(String),
+    IntrinsicGasTooLow(String),
+    /// Transaction gas exceeds the block gas limit.
+    GasLimitExceeded(String),
+    /// Max fee per gas is below the base fee of the block.
+    FeeCapTooLow(String),
+    /// Gas price is too low to enter the pool or replace a pending transaction.
+    Underpriced(String),
+    /// Transaction is already in the pool.
+    AlreadyKnown(String),
+    /// Requested block is unknown or pruned.
+    HeaderNotFound(String),
+    /// `eth_getLogs` range spans too many blocks or matches too many logs. Nodes and providers
+    /// report it with different codes, which is kept.
+    LogRangeTooLarge {
+        code: i32,
+        message: String,
+    },
+    /// -32700
+    ParseError(String),
+    /// -32600
+    InvalidRequest(String),
+    /// -32601
+    MethodNotFound(String),
+    /// -32602
+    InvalidParams(String),
+    /// -32603
+    Internal(String),
+    /// -32000, when the message is not recognized.
+    InvalidInput(String),
+    /// -32001
+    ResourceNotFound(String),
+    /// -32002
+    ResourceUnavailable(String),
+    /// -32003
+    TransactionRejected(String),
+    /// -32004
+    MethodNotSupported(String),
+    /// -32005
+    LimitExceeded(String),
+    /// Any other error.
+    Other {
+        code: i32,
+        message: String,
+        data: Option<serde_json::Value>,
+    },
+}
+
+impl EthRpcError {
+    /// Revert error with the given revert data, as returned by `eth_call` and `eth_estimateGas`.
+    pub fn reverted(data: Bytes) -> Self {
+        Self::ExecutionReverted {
+            message: "execution reverted".into(),
+            data: Some(data),
+        }
+    }
+
+    pub fn code(&self) -> i32 {
+        match self {
+            Self::ExecutionReverted { data: Some(_), .. } => EXECUTION_REVERTED_CODE,
+            Self::ExecutionReverted { data: None, .. }
+            | Self::NonceTooLow(_)
+            | Self::NonceTooHigh(_)
+            | Self::InsufficientFunds(_)
+            | Self::IntrinsicGasTooLow(_)
+            | Self::GasLimitExceeded(_)
+            | Self::FeeCapTooLow(_)
+            | Self::Underpriced(_)
+            | Self::AlreadyKnown(_)
+            | Self::HeaderNotFound(_)
+            | Self::InvalidInput(_) => SERVER_ERROR_CODE,
+            Self::ParseError(_) => -32700,
+            Self::InvalidRequest(_) => -32600,
+            Self::MethodNotFound(_) => -32601,
+            Self::InvalidParams(_) => -32602,
+            Self::Internal(_) => -32603,
+            Self::ResourceNotFound(_) => -32001,
+            Self::ResourceUnavailable(_) => -32002,
+            Self::TransactionRejected(_) => -32003,
+            Self::MethodNotSupported(_) => -32004,
+            Self::LimitExceeded(_) => -32005,
+            Self::LogRangeTooLarge { code, .. } | Self::Other { code, .. } => *code,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::ExecutionReverted { message, .. }
+            | Self::LogRangeTooLarge { message, .. }
+            | Self::Other { message, .. } => message,
+            Self::NonceTooLow(message)
+            | Self::NonceTooHigh(message)
+            | Self::InsufficientFunds(message)
+            | Self::IntrinsicGasTooLow(message)
+            | Self::GasLimitExceeded(message)
+            | Self::FeeCapTooLow(message)
+            | Self::Underpriced(message)
+            | Self::AlreadyKnown(message)
+            | Self::HeaderNotFound(message)
+            | Self::ParseError(message)
+            | Self::InvalidRequest(message)
+            | Self::MethodNotFound(message)
+            | Self::InvalidParams(message)
+            | Self::Internal(message)
+            | Self::InvalidInput(message)
+            | Self::ResourceNotFound(message)
+            | Self::ResourceUnavailable(message)
+            | Self::TransactionRejected(message)
+            | Self::MethodNotSupported(message)
+            | Self::LimitExceeded(message) => message,
+        }
+    }
+
+    /// Revert data of an `ExecutionReverted` error.
+    pub fn revert_data(&self) -> Option<&Bytes> {
+        match self {
+            Self::ExecutionReverted { data, .. } => data.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Decoded revert reason of an `ExecutionReverted` error. Use [`RevertReason::decode_with`] on
+    /// [`Self::revert_data`] to also decode custom errors.
+    pub fn revert_reason(&self) -> Option<RevertReason> {
+        RevertReason::decode(self.revert_data()?.as_ref())
+    }
+
+    /// Classify an error returned by the node.
+    pub fn from_error_object(error: &ErrorObject<'_>) -> Self {
+        let message = error.message().to_string();
+        let data = error
+            .data()
+            .and_then(|data| serde_json::from_str::<serde_json::Value>(data.get()).ok());
+
+        let lowercase = message.to_lowercase();
+        let reverted_data = data
+            .as_ref()
+            .and_then(|data| data.as_str())
+            .is_some_and(|data| data.starts_with("Reverted"));
+        if error.code() == EXECUTION_REVERTED_CODE
+            || lowercase.starts_with("execution reverted")
+            || reverted_data
+        {
+            return Self::ExecutionReverted {
+                message,
+                data: data.as_ref().and_then(revert_data),
+            };
+        }
+
+        // Matched against the message only, codes such as -32005 are shared with rate limits.
+        const LOG_RANGE_MESSAGES: &[&str] = &[
+            // geth, Infura
+            "query returned more than",
+            // geth `--rpc.rangelimit`
+            "exceed maximum block range",
+            // Erigon, Reth
+            "query exceeds max block range",
+            // Alchemy
+            "log response size exceeded",
+            // QuickNode
+            "eth_getlogs is limited to",
+            "eth_getlogs and eth_newfilter are limited to",
+            // Ankr, Cloudflare and others
+            "block range is too wide",
+            "block range is too large",
+            "block range too large",
+        ];
+        if LOG_RANGE_MESSAGES.iter().any(|m| lowercase.contains(m)) {
+            return Self::LogRangeTooLarge {
+                code: error.code(),
+                message,
+            };
+        }
+
+        type Variant = fn(String) -> EthRpcError;
+        const MESSAGES: &[(&str, Variant)] = &[
+            ("nonce too low", EthRpcError::NonceTooLow),
+            ("nonce too high", EthRpcError::NonceTooHigh),
+            ("insufficient funds", EthRpcError::InsufficientFunds),
+            ("intrinsic gas too low", EthRpcError::IntrinsicGasTooLow),
+            ("exceeds block gas limit", EthRpcError::GasLimitExceeded),
+            ("less than block base fee", EthRpcError::FeeCapTooLow),
+            ("transaction underpriced", EthRpcError::Underpriced),
+            ("already known", EthRpcError::AlreadyKnown),
+            ("known transaction", EthRpcError::AlreadyKnown),
+            ("header not found", EthRpcError::HeaderNotFound),
+            ("block not found", EthRpcError::HeaderNotFound),
+        ];
+        if let Some((_, variant)) = MESSAGES.iter().find(|(m, _)| lowercase.contains(m)) {
+            return variant(message);
+        }
+
+        match error.code() {
+            -32700 => Self::ParseError(message),
+            -32600 => Self::InvalidRequest(message),
+            -32601 => Self::MethodNotFound(message),
+            -32602 => Self::InvalidParams(message),
+            -32603 => Self::Internal(message),
+            SERVER_ERROR_CODE => Self::InvalidInput(message),
+            -32001 => Self::ResourceNotFound(message),
+            -32002 => Self::ResourceUnavailable(message),
+            -32003 => Self::TransactionRejected(message),
+            -32004 => Self::MethodNotSupported(message),
+            -32005 => Self::LimitExceeded(message),
+            code => Self::Other {
+                code,
+                message,
+                data,
+            },
+        }
+    }
+
+    /// Classify an error returned by a client call, `None` if the node did not return an error
+    /// object (e.g. on transport errors).
+    #[cfg(feature = "client")]
+    pub fn from_client_error(error: &jsonrpsee::core::Error) -> Option<Self> {
+        match error {
+            jsonrpsee::core::Error::Call(jsonrpsee::types::error::CallError::Custom(error)) => {
+                Some(Self::from_error_object(error))
+            }
+            _ => None,
+        }
+    }
+
+    /// Error object to respond with.
+    pub fn to_error_object(&self) -> ErrorObject<'static> {
+        let data = match self {
+            Self::ExecutionReverted { data, .. } => data.as_ref().map(serde_json::to_value),
+            Self::Other { data, .. } => data.clone().map(Ok),
+            _ => None,
+        }
+        .and_then(Result::ok);
+        ErrorObject::owned(self.code(), self.message(), data)
+    }
+}
+
+/// Revert data as sent by geth (`"0x..."`) or older erigon and nethermind (`"Reverted 0x..."`).
+fn revert_data(data: &serde_json::Value) -> Option<Bytes> {
+    let data = data.as_str()?;
+    let hex = &data[data.find("0x")?..];
+    serde_json::from_value(serde_json::Value::String(hex.to_string())).ok()
+}
+
+impl std::fmt::Display for EthRpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (code {})", self.message(), self.code())
+    }
+}
+
+impl std::error::Error for EthRpcError {}
+
+impl From<&ErrorObject<'_>> for EthRpcError {
+    fn from(error: &ErrorObject<'_>) -> Self {
+        Self::from_error_object(error)
+    }
+}
+
+impl From<EthRpcError> for ErrorObject<'static> {
+    fn from(error: EthRpcError) -> Self {
+        error.to_error_object()
+    }
+}
+
+#[cfg(feature = "server")]
+impl From<EthRpcError> for jsonrpsee::core::Error {
+    fn from(error: EthRpcError) -> Self {
+        jsonrpsee::types::error::CallError::Custom(error.into()).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use serde_json::json;
+
+    fn parse(error: serde_json::Value) -> EthRpcError {
+        EthRpcError::from_error_object(&serde_json::from_value::<ErrorObject>(error).unwrap())
+    }
+
+    #[test]
+    fn classify_errors() {
+        let revert = parse(json!({
+            "code": 3,
+            "message": "execution reverted: Insufficient balance",
+            "data": "0x08c379a000000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000014496e73756666696369656e742062616c616e6365000000000000000000000000",
+        }));
+        assert_eq!(revert.code(), 3);
+        assert_eq!(revert.message(), "execution reverted: Insufficient balance");
+        assert_eq!(
+            revert.revert_data().unwrap().as_ref()[..4],
+            hex!("08c379a0")
+        );
+        assert_eq!(
+            revert.revert_reason(),
+            Some(RevertReason::Error("Insufficient balance".into()))
+        );
+
+        assert_eq!(
+            parse(json!({"code": -32000, "message": "execution reverted"})),
+            EthRpcError::ExecutionReverted {
+                message: "execution reverted".into(),
+                data: None,
+            }
+        );
+        assert_eq!(
+            parse(json!({
+                "code": -32000,
+                "message": "VM execution error.",
+                "data": "Reverted 0xdeadbeef",
+            })),
+            EthRpcError::ExecutionReverted {
+                message: "VM execution error.".into(),
+                data: Some(hex!("deadbeef").to_vec().into()),
+            }
+        );
+        assert_eq!(
+            parse(json!({
+                "code": -32015,
+                "message": "execution reverted",
+                "data": "Reverted 0xdeadbeef",
+            }))
+            .revert_data()
+            .map(|data| data.as_ref().to_vec()),
+            Some(hex!("deadbeef").to_vec())
+        );
+
+        for (error, expected) in [
+            (
+                json!({"code": -32000, "message": "nonce too low: address 0x01, tx: 1 state: 2"}),
+                EthRpcError::NonceTooLow("nonce too low: address 0x01, tx: 1 state: 2".into()),
+            ),
+            (
+                json!({"code": -32000, "message": "insufficient funds for gas * price + value"}),
+                EthRpcError::InsufficientFunds("insufficient funds for gas * price + value".into()),
+            ),
+            (
+                json!({"code": -32000, "message": "replacement transaction underpriced"}),
+                EthRpcError::Underpriced("replacement transaction underpriced".into()),
+            ),
+            (
+                json!({"code": -32000, "message": "header not found"}),
+                EthRpcError::HeaderNotFound("header not found".into()),
+            ),
+            (
+                json!({"code": -32601, "message": "the method eth_foo does not exist"}),
+                EthRpcError::MethodNotFound("the method eth_foo does not exist".into()),
+            ),
+            (
+                json!({"code": -32005, "message": "limit exceeded"}),
+                EthRpcError::LimitExceeded("limit exceeded".into()),
+            ),
+            (
+                json!({"code": -32000, "message": "something else"}),
+                EthRpcError::InvalidInput("something else".into()),
+            ),
+        ] {
+            assert_eq!(parse(error), expected);
+        }
+    }
+
+    #[test]
+    fn classify_log_range_errors() {
+        for (code, message) in [
+            (-32005, "query returned more than 10000 results"),
+            (-32000, "exceed maximum block range: 50000"),
+            (-32000, "query exceeds max block range 100000"),
+            (-32602, "Log response size exceeded. You can make eth_getLogs requests with up to a 2K block range and no limit on the response size, or you can request any block range with a cap of 10K logs in the response."),
+            (-32602, "eth_getLogs is limited to a 10,000 range"),
+            (-32600, "block range is too wide"),
+        ] {
+            assert_eq!(
+                parse(json!({"code": code, "message": message})),
+                EthRpcError::LogRangeTooLarge {
+                    code,
+                    message: message.into(),
+                },
+            );
+        }
+
+        for (code, message) in [
+            (-32005, "limit exceeded"),
+            (-32005, "rate limit exceeded"),
+            (-32005, "daily request count exceeded, request rate limited"),
+            (429, "Too many requests, exceeded 25 requests per second"),
+            (-32000, "missing trie node 0000000000000000000000000000000000000000000000000000000000000000 (path ) state is not available"),
+            (-32000, "historical state is not available, the range is pruned"),
+        ] {
+            assert!(
+                !matches!(
+                    parse(json!({"code": code, "message": message})),
+                    EthRpcError::LogRangeTooLarge { .. }
+                ),
+                "{message}"
+            );
+        }
+    }
+
+    #[test]
+    fn build_error_objects() {
+        for (error, expected) in [
+            (
+                EthRpcError::reverted(hex!("deadbeef").to_vec().into()),
+                json!({"code": 3, "message": "execution reverted", "data": "0xdeadbeef"}),
+            ),
+            (
+                EthRpcError::NonceTooLow("nonce too low".into()),
+                json!({"code": -32000, "message": "nonce too low"}),
+            ),
+            (
+                EthRpcError::LimitExceeded("too many results".into()),
+                json!({"code": -32005, "message": "too many results"}),
+            ),
+            (
+                EthRpcError::LogRangeTooLarge {
+                    code: -32602,
+                    message: "block range is too wide".into(),
+                },
+                json!({"code": -32602, "message": "block range is too wide"}),
+            ),
+            (
+                EthRpcError::Other {
+                    code: 4001,
+                    message: "rejected".into(),
+                    data: Some(json!({"reason": 1})),
+                },
+                json!({"code": 4001, "message": "rejected", "data": {"reason": 1}}),
+            ),
+        ] {
+            let object = error.to_error_object();
+            assert_eq!(serde_json::to_value(&object).unwrap(), expected);
+            assert_eq!(EthRpcError::from_error_object(&object), error);
+        }
+    }
+}
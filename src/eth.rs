@@ -1,5 +1,7 @@
 #[allow(unused_imports)]
 use crate::prelude::*;
+use ethereum_types::BloomInput;
+use std::collections::BTreeMap;
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -34,6 +36,345 @@ pub struct LogFilter {
     pub topics: Option<ArrayVec<Option<LogTopicFilter>, 4>>,
 }
 
+impl LogAddressFilter {
+    fn matches(&self, address: &Address) -> bool {
+        self.0.is_empty() || self.0.contains(address)
+    }
+
+    fn may_match(&self, bloom: &Bloom) -> bool {
+        self.0.is_empty()
+            || self
+                .0
+                .iter()
+                .any(|address| bloom.contains_input(BloomInput::Raw(address.as_bytes())))
+    }
+}
+
+impl LogTopicFilter {
+    fn matches(&self, topic: &H256) -> bool {
+        self.0.is_empty() || self.0.contains(topic)
+    }
+
+    fn may_match(&self, bloom: &Bloom) -> bool {
+        self.0.is_empty()
+            || self
+                .0
+                .iter()
+                .any(|topic| bloom.contains_input(BloomInput::Raw(topic.as_bytes())))
+    }
+}
+
+impl LogFilter {
+    /// Whether the log satisfies this filter.
+    ///
+    /// Any of the addresses must match, and at every position the topic must match any of the
+    /// filter's topics. `None` and empty lists match anything, but a log with fewer topics than the
+    /// filter does not match. Only block numbers and hashes are checked against the block filter,
+    /// tags such as `latest` are treated as unbounded.
+    pub fn matches(&self, log: &TransactionLog) -> bool {
+        let block_matches = match &self.block_filter {
+            Some(BlockFilter::Exact { block_hash }) => log.block_hash == Some(*block_hash),
+            Some(BlockFilter::Bounded {
+                from_block,
+                to_block,
+            }) => {
+                let bound = |block: &Option<BlockNumber>| match block {
+                    Some(BlockNumber::Number(n)) => Some(*n),
+                    Some(BlockNumber::Earliest) => Some(U64::zero()),
+                    _ => None,
+                };
+                match log.block_number {
+                    Some(n) => {
+                        bound(from_block).is_none_or(|from| from <= n)
+                            && bound(to_block).is_none_or(|to| n <= to)
+                    }
+                    None => true,
+                }
+            }
+            None => true,
+        };
+
+        block_matches
+            && self
+                .address
+                .as_ref()
+                .is_none_or(|filter| filter.matches(&log.address))
+            && self.topics.as_ref().is_none_or(|topics| {
+                topics.len() <= log.topics.len()
+                    && topics
+                        .iter()
+                        .zip(&log.topics)
+                        .all(|(filter, topic)| filter.as_ref().is_none_or(|f| f.matches(topic)))
+            })
+    }
+
+    /// Whether a block or receipt with the given `logs_bloom` may contain logs matching this filter.
+    ///
+    /// A `false` result is definitive, `true` may be a false positive.
+    pub fn may_match(&self, bloom: &Bloom) -> bool {
+        self.address
+            .as_ref()
+            .is_none_or(|filter| filter.may_match(bloom))
+            && self.topics.as_ref().is_none_or(|topics| {
+                topics
+                    .iter()
+                    .flatten()
+                    .all(|filter| filter.may_match(bloom))
+            })
+    }
+}
+
+/// Identifier of a filter installed on the node.
+///
+/// Kept as an opaque string, since nodes compare it verbatim (including leading zeros).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FilterId(pub String);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+/// Result of `eth_getFilterChanges`, its shape depends on the filter kind.
+///
+/// An empty response is indistinguishable between the two and deserializes into `Hashes`.
+pub enum FilterChanges {
+    /// Block or pending transaction hashes.
+    Hashes(Vec<H256>),
+    /// Logs matching a log filter.
+    Logs(Vec<TransactionLog>),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Result of `eth_feeHistory`.
+pub struct FeeHistory {
+    /// Lowest block number of the returned range.
+    pub oldest_block: U64,
+    /// Base fee per gas for each block in the range, plus the one following the newest block.
+    pub base_fee_per_gas: Vec<U256>,
+    /// Ratio of gas used to gas limit for each block in the range.
+    pub gas_used_ratio: Vec<f64>,
+    /// Effective priority fees at the requested percentiles, per block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reward: Option<Vec<Vec<U256>>>,
+    /// Base fee per blob gas for each block in the range, plus the one following the newest block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_fee_per_blob_gas: Option<Vec<U256>>,
+    /// Ratio of blob gas used to maximum blob gas for each block in the range.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_gas_used_ratio: Option<Vec<f64>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Account fields to replace before executing a call.
+///
+/// `state` replaces the whole storage, while `state_diff` patches individual slots, only one of
+/// them may be set.
+pub struct AccountOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<U64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<BTreeMap<H256, H256>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<BTreeMap<H256, H256>>,
+}
+
+/// Per-account state overrides for `eth_call` and `eth_estimateGas`.
+pub type StateOverride = BTreeMap<Address, AccountOverride>;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Block context overrides for `eth_call` and `eth_estimateGas`.
+pub struct BlockOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number: Option<U64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<U64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_limit: Option<U64>,
+    #[serde(
+        rename = "feeRecipient",
+        alias = "coinbase",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub coinbase: Option<Address>,
+    #[serde(
+        rename = "prevRandao",
+        alias = "random",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub random: Option<H256>,
+    #[serde(
+        rename = "baseFeePerGas",
+        alias = "baseFee",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub base_fee: Option<U256>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Block of calls to simulate with `eth_simulateV1`.
+pub struct SimBlock {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_overrides: Option<BlockOverrides>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_overrides: Option<StateOverride>,
+    #[serde(default)]
+    pub calls: Vec<MessageCall>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Request of `eth_simulateV1`.
+pub struct SimulatePayload {
+    /// Blocks to simulate, each on top of the previous one.
+    pub block_state_calls: Vec<SimBlock>,
+    /// Report ETH transfers as logs.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub trace_transfers: bool,
+    /// Validate calls as if they were real transactions (nonces, balances, base fee).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub validation: bool,
+    /// Return full transaction objects instead of hashes.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub return_full_transactions: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimCallError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Bytes>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Outcome of a single simulated call.
+pub struct SimCallResult {
+    pub return_data: Bytes,
+    pub logs: Vec<TransactionLog>,
+    pub gas_used: U64,
+    /// Status, 1 for success, 0 for failure.
+    pub status: U64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<SimCallError>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Block produced by `eth_simulateV1`.
+pub struct SimulatedBlock {
+    #[serde(flatten)]
+    pub inner: Block,
+    pub calls: Vec<SimCallResult>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Result of `eth_createAccessList`.
+pub struct AccessListResult {
+    /// Access list touched by the call.
+    pub access_list: Vec<AccessListEntry>,
+    /// Gas used by the call with the access list applied.
+    pub gas_used: U64,
+    /// Execution error, if the call failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Suggested fees for an EIP-1559 transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeEstimate {
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+}
+
+impl FeeEstimate {
+    /// Priority fee used when the fee history carries no non-zero rewards.
+    pub const DEFAULT_PRIORITY_FEE: U256 = U256::new(1_000_000_000);
+
+    /// Estimate with a max fee that leaves room for `next_base_fee` to double, saturating at
+    /// `U256::MAX`.
+    pub fn with_priority_fee(next_base_fee: U256, max_priority_fee_per_gas: U256) -> Self {
+        Self {
+            max_fee_per_gas: next_base_fee
+                .saturating_mul(U256::new(2))
+                .saturating_add(max_priority_fee_per_gas),
+            max_priority_fee_per_gas,
+        }
+    }
+
+    /// Fill fee fields of an EIP-1559, EIP-4844 or EIP-7702 message call. Other variants are left
+    /// untouched.
+    pub fn apply(&self, call: &mut MessageCall) {
+        if let MessageCall::EIP1559 {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            ..
+        }
+        | MessageCall::EIP4844 {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            ..
+        }
+        | MessageCall::EIP7702 {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            ..
+        } = call
+        {
+            *max_fee_per_gas = Some(self.max_fee_per_gas);
+            *max_priority_fee_per_gas = Some(self.max_priority_fee_per_gas);
+        }
+    }
+}
+
+impl FeeHistory {
+    /// Suggest EIP-1559 fees based on this history.
+    ///
+    /// The priority fee is the median of non-zero rewards at `percentile_index` (index into the
+    /// `reward_percentiles` the history was requested with). The max fee leaves room for the base
+    /// fee of the next block to double.
+    ///
+    /// Returns `None` if the history contains no base fees.
+    pub fn estimate_fees(&self, percentile_index: usize) -> Option<FeeEstimate> {
+        let next_base_fee = *self.base_fee_per_gas.last()?;
+
+        let mut rewards = self
+            .reward
+            .iter()
+            .flatten()
+            .filter_map(|block_rewards| block_rewards.get(percentile_index).copied())
+            .filter(|reward| *reward > 0)
+            .collect::<Vec<_>>();
+        rewards.sort_unstable();
+
+        let max_priority_fee_per_gas = if rewards.is_empty() {
+            FeeEstimate::DEFAULT_PRIORITY_FEE
+        } else if rewards.len() % 2 == 0 {
+            let mid = rewards.len() / 2;
+            rewards[mid - 1] + (rewards[mid] - rewards[mid - 1]) / 2
+        } else {
+            rewards[rewards.len() / 2]
+        };
+
+        Some(FeeEstimate::with_priority_fee(
+            next_base_fee,
+            max_priority_fee_per_gas,
+        ))
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SyncStatus {
     NotSyncing,
@@ -116,6 +457,151 @@ impl<'de> Deserialize<'de> for SyncStatus {
     }
 }
 
+/// Progress reported by a `syncing` subscription.
+///
+/// geth sends the fields capitalized and as plain numbers, other clients camelCase quantities;
+/// both are accepted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncProgress {
+    #[serde(alias = "StartingBlock", deserialize_with = "deserialize_quantity")]
+    pub starting_block: U64,
+    #[serde(alias = "CurrentBlock", deserialize_with = "deserialize_quantity")]
+    pub current_block: U64,
+    #[serde(alias = "HighestBlock", deserialize_with = "deserialize_quantity")]
+    pub highest_block: U64,
+}
+
+fn deserialize_quantity<'de, D>(deserializer: D) -> Result<U64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Quantity {
+        Hex(U64),
+        Number(u64),
+    }
+
+    Ok(match Quantity::deserialize(deserializer)? {
+        Quantity::Hex(n) => n,
+        Quantity::Number(n) => n.into(),
+    })
+}
+
+/// Notification delivered by a `syncing` subscription: `false` once the node is in sync,
+/// otherwise `{"syncing": true, "status": {...}}`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncingNotification {
+    NotSyncing,
+    Syncing(SyncProgress),
+}
+
+#[derive(Serialize, Deserialize)]
+struct SyncingResult {
+    syncing: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    status: Option<SyncProgress>,
+}
+
+impl Serialize for SyncingNotification {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self {
+            SyncingNotification::NotSyncing => false.serialize(serializer),
+            SyncingNotification::Syncing(status) => SyncingResult {
+                syncing: true,
+                status: Some(status),
+            }
+            .serialize(serializer),
+        }
+    }
+}
+
+struct SyncingNotificationVisitor;
+
+impl<'de> Visitor<'de> for SyncingNotificationVisitor {
+    type Value = SyncingNotification;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "false or a syncing result object")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if !v {
+            Ok(Self::Value::NotSyncing)
+        } else {
+            Err(de::Error::invalid_type(de::Unexpected::Bool(v), &self))
+        }
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        match SyncingResult::deserialize(MapAccessDeserializer::new(map))? {
+            SyncingResult { syncing: false, .. } => Ok(Self::Value::NotSyncing),
+            SyncingResult {
+                status: Some(status),
+                ..
+            } => Ok(Self::Value::Syncing(status)),
+            SyncingResult { status: None, .. } => Err(de::Error::missing_field("status")),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SyncingNotification {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(SyncingNotificationVisitor)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SubscriptionKind {
+    /// New block headers as they are added to the chain.
+    NewHeads,
+    /// Logs matching a filter, included in new blocks.
+    Logs,
+    /// Transactions entering the pending pool.
+    NewPendingTransactions,
+    /// Changes of the node's sync status.
+    Syncing,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SubscriptionParams {
+    /// Filter for `logs` subscription.
+    Logs(Box<LogFilter>),
+    /// Whether `newPendingTransactions` should yield full transaction objects instead of hashes.
+    FullTransactions(bool),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+/// Notification delivered by `eth_subscribe`.
+pub enum PubSubItem {
+    /// Pending transaction hash.
+    TransactionHash(H256),
+    /// New block header.
+    Header(Box<Header>),
+    /// Log matching the subscription filter.
+    Log(Box<TransactionLog>),
+    /// Full pending transaction.
+    Transaction(Box<Transaction>),
+    /// Sync status change.
+    Syncing(SyncingNotification),
+}
+
 #[cfg(any(feature = "client", feature = "server"))]
 #[cfg_attr(feature = "client", rpc(client, namespace = "eth"))]
 #[cfg_attr(feature = "server", rpc(server, namespace = "eth"))]
@@ -125,19 +611,33 @@ pub trait EthApi {
     #[method(name = "chainId")]
     async fn chain_id(&self) -> RpcResult<U64>;
     #[method(name = "call")]
-    async fn call(&self, call_data: MessageCall, block_number: BlockNumber) -> RpcResult<Bytes>;
+    async fn call(
+        &self,
+        call_data: MessageCall,
+        block_id: BlockId,
+        state_overrides: Option<StateOverride>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> RpcResult<Bytes>;
     #[method(name = "estimateGas")]
     async fn estimate_gas(
         &self,
         call_data: MessageCall,
-        block_number: BlockNumber,
+        block_id: BlockId,
+        state_overrides: Option<StateOverride>,
     ) -> RpcResult<U64>;
     #[method(name = "gasPrice")]
     async fn gas_price(&self) -> RpcResult<U256>;
     #[method(name = "maxPriorityFeePerGas")]
     async fn max_priority_fee_per_gas(&self) -> RpcResult<U256>;
+    #[method(name = "feeHistory")]
+    async fn fee_history(
+        &self,
+        block_count: U64,
+        newest_block: BlockNumber,
+        reward_percentiles: Vec<f64>,
+    ) -> RpcResult<FeeHistory>;
     #[method(name = "getBalance")]
-    async fn get_balance(&self, address: Address, block_number: BlockNumber) -> RpcResult<U256>;
+    async fn get_balance(&self, address: Address, block_id: BlockId) -> RpcResult<U256>;
     #[method(name = "getBlockByHash")]
     async fn get_block_by_hash(
         &self,
@@ -158,13 +658,13 @@ pub trait EthApi {
         block_number: BlockNumber,
     ) -> RpcResult<U64>;
     #[method(name = "getCode")]
-    async fn get_code(&self, address: Address, block_number: BlockNumber) -> RpcResult<Bytes>;
+    async fn get_code(&self, address: Address, block_id: BlockId) -> RpcResult<Bytes>;
     #[method(name = "getStorageAt")]
     async fn get_storage_at(
         &self,
         address: Address,
         storage_pos: U256,
-        block_number: BlockNumber,
+        block_id: BlockId,
     ) -> RpcResult<U256>; // Storage data is nothing more than 32-bytes
     #[method(name = "getTransactionByHash")]
     async fn get_transaction_by_hash(&self, hash: H256) -> RpcResult<Option<Tx>>;
@@ -181,14 +681,15 @@ pub trait EthApi {
         index: U64,
     ) -> RpcResult<Option<Tx>>;
     #[method(name = "getTransactionCount")]
-    async fn get_transaction_count(
-        &self,
-        address: Address,
-        block_number: BlockNumber,
-    ) -> RpcResult<U64>;
+    async fn get_transaction_count(&self, address: Address, block_id: BlockId) -> RpcResult<U64>;
     #[method(name = "getTransactionReceipt")]
     async fn get_transaction_receipt(&self, tx_hash: H256)
         -> RpcResult<Option<TransactionReceipt>>;
+    #[method(name = "getBlockReceipts")]
+    async fn get_block_receipts(
+        &self,
+        block_id: BlockId,
+    ) -> RpcResult<Option<Vec<TransactionReceipt>>>;
     #[method(name = "getUncleByBlockHashAndIndex")]
     async fn get_uncle_by_block_hash_and_index(
         &self,
@@ -205,10 +706,657 @@ pub trait EthApi {
     async fn get_uncle_count_by_block_hash(&self, block_hash: H256) -> RpcResult<U64>;
     #[method(name = "getUncleCountByBlockNumber")]
     async fn get_uncle_count_by_block_number(&self, block_number: BlockNumber) -> RpcResult<U64>;
+    #[method(name = "getProof")]
+    async fn get_proof(
+        &self,
+        address: Address,
+        storage_keys: Vec<H256>,
+        block_id: BlockId,
+    ) -> RpcResult<AccountProof>;
+    #[method(name = "createAccessList")]
+    async fn create_access_list(
+        &self,
+        call_data: MessageCall,
+        block_id: BlockId,
+    ) -> RpcResult<AccessListResult>;
+    #[method(name = "simulateV1")]
+    async fn simulate_v1(
+        &self,
+        payload: SimulatePayload,
+        block_id: BlockId,
+    ) -> RpcResult<Vec<SimulatedBlock>>;
     #[method(name = "getLogs")]
     async fn get_logs(&self, filter: LogFilter) -> RpcResult<Vec<TransactionLog>>;
     #[method(name = "syncing")]
     async fn syncing(&self) -> RpcResult<SyncStatus>;
+    #[method(name = "sendRawTransaction")]
+    async fn send_raw_transaction(&self, tx: Bytes) -> RpcResult<H256>;
+    #[method(name = "sendTransaction")]
+    async fn send_transaction(&self, tx: MessageCall) -> RpcResult<H256>;
+    #[method(name = "newFilter")]
+    async fn new_filter(&self, filter: LogFilter) -> RpcResult<FilterId>;
+    #[method(name = "newBlockFilter")]
+    async fn new_block_filter(&self) -> RpcResult<FilterId>;
+    #[method(name = "newPendingTransactionFilter")]
+    async fn new_pending_transaction_filter(&self) -> RpcResult<FilterId>;
+    #[method(name = "getFilterChanges")]
+    async fn get_filter_changes(&self, filter_id: FilterId) -> RpcResult<FilterChanges>;
+    #[method(name = "getFilterLogs")]
+    async fn get_filter_logs(&self, filter_id: FilterId) -> RpcResult<Vec<TransactionLog>>;
+    #[method(name = "uninstallFilter")]
+    async fn uninstall_filter(&self, filter_id: FilterId) -> RpcResult<bool>;
+}
+
+#[cfg(any(feature = "client", feature = "server"))]
+#[cfg_attr(feature = "client", rpc(client, namespace = "eth"))]
+#[cfg_attr(feature = "server", rpc(server, namespace = "eth"))]
+pub trait EthPubSubApi {
+    /// Create an `eth_subscribe` subscription.
+    ///
+    /// The item type depends on `kind`, see [`EthPubSubClientExt`] for typed streams.
+    #[subscription(name = "subscribe" => "subscription", unsubscribe = "unsubscribe", item = PubSubItem)]
+    fn subscribe_items(&self, kind: SubscriptionKind, options: Option<SubscriptionParams>);
+}
+
+/// Typed `eth_subscribe` streams for any subscription-capable client.
+#[cfg(feature = "client")]
+#[jsonrpsee::core::async_trait]
+pub trait EthPubSubClientExt: SubscriptionClientT + Sync {
+    async fn subscribe_new_heads(&self) -> Result<Subscription<Header>, jsonrpsee::core::Error> {
+        self.subscribe(
+            "eth_subscribe",
+            rpc_params![SubscriptionKind::NewHeads],
+            "eth_unsubscribe",
+        )
+        .await
+    }
+
+    async fn subscribe_logs(
+        &self,
+        filter: LogFilter,
+    ) -> Result<Subscription<TransactionLog>, jsonrpsee::core::Error> {
+        self.subscribe(
+            "eth_subscribe",
+            rpc_params![SubscriptionKind::Logs, filter],
+            "eth_unsubscribe",
+        )
+        .await
+    }
+
+    async fn subscribe_pending_transaction_hashes(
+        &self,
+    ) -> Result<Subscription<H256>, jsonrpsee::core::Error> {
+        self.subscribe(
+            "eth_subscribe",
+            rpc_params![SubscriptionKind::NewPendingTransactions],
+            "eth_unsubscribe",
+        )
+        .await
+    }
+
+    async fn subscribe_pending_transactions(
+        &self,
+    ) -> Result<Subscription<Transaction>, jsonrpsee::core::Error> {
+        self.subscribe(
+            "eth_subscribe",
+            rpc_params![SubscriptionKind::NewPendingTransactions, true],
+            "eth_unsubscribe",
+        )
+        .await
+    }
+
+    async fn subscribe_syncing(
+        &self,
+    ) -> Result<Subscription<SyncingNotification>, jsonrpsee::core::Error> {
+        self.subscribe(
+            "eth_subscribe",
+            rpc_params![SubscriptionKind::Syncing],
+            "eth_unsubscribe",
+        )
+        .await
+    }
+}
+
+#[cfg(feature = "client")]
+impl<T: SubscriptionClientT + Sync> EthPubSubClientExt for T {}
+
+/// Convenience over [`EthApiClient::call`] and [`EthApiClient::estimate_gas`] that leaves unset
+/// trailing overrides off the request instead of sending them as `null`, which some nodes reject
+/// as too many arguments.
+#[cfg(feature = "client")]
+#[jsonrpsee::core::async_trait]
+pub trait EthCallClientExt: ClientT + Sync {
+    async fn call_with_overrides(
+        &self,
+        call_data: MessageCall,
+        block_id: BlockId,
+        state_override: Option<StateOverride>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> Result<Bytes, jsonrpsee::core::Error> {
+        let params = override_params(call_data, block_id, state_override, block_overrides)?;
+        self.request("eth_call", params).await
+    }
+
+    async fn estimate_gas_with_overrides(
+        &self,
+        call_data: MessageCall,
+        block_id: BlockId,
+        state_override: Option<StateOverride>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> Result<U64, jsonrpsee::core::Error> {
+        let params = override_params(call_data, block_id, state_override, block_overrides)?;
+        self.request("eth_estimateGas", params).await
+    }
+}
+
+#[cfg(feature = "client")]
+impl<T: ClientT + Sync> EthCallClientExt for T {}
+
+/// Positional params for a call, stopping at the last override that is set.
+#[cfg(feature = "client")]
+fn override_params(
+    call_data: MessageCall,
+    block_id: BlockId,
+    state_override: Option<StateOverride>,
+    block_overrides: Option<BlockOverrides>,
+) -> Result<ArrayParams, jsonrpsee::core::Error> {
+    let mut params = ArrayParams::new();
+    params.insert(call_data)?;
+    params.insert(block_id)?;
+    if state_override.is_some() || block_overrides.is_some() {
+        params.insert(state_override)?;
+    }
+    if let Some(block_overrides) = block_overrides {
+        params.insert(block_overrides)?;
+    }
+    Ok(params)
+}
+
+#[cfg(feature = "client")]
+async fn resolve_block_number(
+    client: &(impl EthApiClient + Sync),
+    block: Option<BlockNumber>,
+) -> Result<U64, jsonrpsee::core::Error> {
+    match block {
+        Some(BlockNumber::Number(n)) => Ok(n),
+        Some(BlockNumber::Earliest) => Ok(U64::zero()),
+        None | Some(BlockNumber::Latest) => client.block_number().await,
+        Some(tag) => client
+            .get_block_by_number(tag, false)
+            .await?
+            .and_then(|block| block.number)
+            .ok_or_else(|| jsonrpsee::core::Error::Custom(format!("block {tag:?} not found"))),
+    }
+}
+
+/// Stream the logs of a `BlockFilter::Bounded` filter, splitting the range into requests of at most
+/// `max_block_range` blocks.
+///
+/// Tags in the range are resolved once, up front. Whenever the node rejects a request as spanning
+/// too many blocks or results, the chunk is halved and retried, it grows back after successful
+/// requests. Logs are yielded in block order, the stream ends after the first error.
+#[cfg(feature = "client")]
+pub fn get_logs_chunked<C: EthApiClient + Sync>(
+    client: &C,
+    filter: LogFilter,
+    max_block_range: u64,
+) -> impl futures::Stream<Item = Result<TransactionLog, jsonrpsee::core::Error>> + '_ {
+    struct State {
+        filter: LogFilter,
+        /// Next block to request, `None` until the range is resolved.
+        from: Option<u64>,
+        to: u64,
+        chunk: u64,
+        logs: std::vec::IntoIter<TransactionLog>,
+    }
+
+    let max_block_range = max_block_range.max(1);
+    let state = State {
+        filter,
+        from: None,
+        to: 0,
+        chunk: max_block_range,
+        logs: Vec::new().into_iter(),
+    };
+
+    futures::stream::unfold(Some(state), move |state| async move {
+        let mut state = state?;
+        loop {
+            if let Some(log) = state.logs.next() {
+                return Some((Ok(log), Some(state)));
+            }
+
+            let from = match state.from {
+                Some(from) => from,
+                None => {
+                    let Some(BlockFilter::Bounded {
+                        from_block,
+                        to_block,
+                    }) = state.filter.block_filter.clone()
+                    else {
+                        let e = jsonrpsee::core::Error::Custom(
+                            "chunked eth_getLogs requires a bounded block filter".into(),
+                        );
+                        return Some((Err(e), None));
+                    };
+                    let range = async {
+                        Ok::<_, jsonrpsee::core::Error>((
+                            resolve_block_number(client, from_block).await?,
+                            resolve_block_number(client, to_block).await?,
+                        ))
+                    };
+                    match range.await {
+                        Ok((from, to)) => {
+                            state.to = to.as_u64();
+                            from.as_u64()
+                        }
+                        Err(e) => return Some((Err(e), None)),
+                    }
+                }
+            };
+            if from > state.to {
+                return None;
+            }
+
+            let end = state.to.min(from.saturating_add(state.chunk - 1));
+            let request = LogFilter {
+                block_filter: Some(BlockFilter::Bounded {
+                    from_block: Some(BlockNumber::Number(from.into())),
+                    to_block: Some(BlockNumber::Number(end.into())),
+                }),
+                ..state.filter.clone()
+            };
+            match client.get_logs(request).await {
+                Ok(logs) => {
+                    state.logs = logs.into_iter();
+                    state.from = Some(end + 1);
+                    state.chunk = state.chunk.saturating_mul(2).min(max_block_range);
+                }
+                Err(e)
+                    if state.chunk > 1
+                        && matches!(
+                            crate::EthRpcError::from_client_error(&e),
+                            Some(crate::EthRpcError::LogRangeTooLarge { .. })
+                        ) =>
+                {
+                    state.from = Some(from);
+                    state.chunk /= 2;
+                }
+                Err(e) => return Some((Err(e), None)),
+            }
+        }
+    })
+}
+
+/// Completes a [`MessageCall`] into a [`TransactionMessage`] ready to be signed, querying the node
+/// for the chain id, the pending nonce and whatever gas and fee fields the call leaves out.
+///
+/// Untyped calls without a gas price become EIP-1559 transactions if the chain has a base fee,
+/// and legacy or EIP-2930 transactions priced at `eth_gasPrice` otherwise.
+#[cfg(feature = "client")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransactionFiller {
+    /// Factor applied to `eth_estimateGas`, as headroom for state changing before inclusion. Must
+    /// be finite and at least 1, the result is capped at the gas limit of the latest block.
+    pub gas_multiplier: f64,
+    /// Number of blocks of `eth_feeHistory` fees are estimated from.
+    pub fee_history_blocks: u64,
+    /// Reward percentile the priority fee is estimated at.
+    pub reward_percentile: f64,
+}
+
+#[cfg(feature = "client")]
+impl Default for TransactionFiller {
+    fn default() -> Self {
+        Self {
+            gas_multiplier: 1.2,
+            fee_history_blocks: 10,
+            reward_percentile: 50.0,
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+impl TransactionFiller {
+    /// Fill `call`, which must have `from` set.
+    pub async fn fill<C: EthApiClient + Sync>(
+        &self,
+        client: &C,
+        call: MessageCall,
+    ) -> Result<TransactionMessage, jsonrpsee::core::Error> {
+        if !self.gas_multiplier.is_finite() || self.gas_multiplier < 1.0 {
+            return Err(jsonrpsee::core::Error::Custom(format!(
+                "gas multiplier must be finite and at least 1, got {}",
+                self.gas_multiplier
+            )));
+        }
+        let from = match &call {
+            MessageCall::Legacy { from, .. }
+            | MessageCall::EIP2930 { from, .. }
+            | MessageCall::EIP1559 { from, .. }
+            | MessageCall::EIP4844 { from, .. }
+            | MessageCall::EIP7702 { from, .. } => *from,
+        }
+        .ok_or_else(|| missing_field("from"))?;
+
+        let chain_id = client.chain_id().await?;
+        let nonce = client
+            .get_transaction_count(from, BlockNumber::Pending.into())
+            .await?;
+        let mut call = self.fill_fees(client, call).await?;
+
+        if gas_mut(&mut call).is_none() {
+            let estimate = client
+                .estimate_gas_with_overrides(call.clone(), BlockNumber::Latest.into(), None, None)
+                .await?;
+            let gas_limit = client
+                .get_block_by_number(BlockNumber::Latest, false)
+                .await?
+                .ok_or_else(|| jsonrpsee::core::Error::Custom("latest block not found".into()))?
+                .gas_limit;
+            // The cast saturates, the cap keeps the result includable.
+            let gas = (estimate.as_u64() as f64 * self.gas_multiplier).ceil() as u64;
+            *gas_mut(&mut call) = Some(gas.min(gas_limit.as_u64()).into());
+        }
+
+        into_transaction_message(call, chain_id, nonce)
+    }
+
+    /// Choose the transaction type and fill in its missing fee fields.
+    async fn fill_fees<C: EthApiClient + Sync>(
+        &self,
+        client: &C,
+        mut call: MessageCall,
+    ) -> Result<MessageCall, jsonrpsee::core::Error> {
+        let needs_fees = match &call {
+            MessageCall::Legacy { gas_price, .. } | MessageCall::EIP2930 { gas_price, .. } => {
+                gas_price.is_none()
+            }
+            MessageCall::EIP1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                ..
+            }
+            | MessageCall::EIP7702 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                ..
+            } => max_fee_per_gas.is_none() || max_priority_fee_per_gas.is_none(),
+            MessageCall::EIP4844 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                max_fee_per_blob_gas,
+                ..
+            } => {
+                max_fee_per_gas.is_none()
+                    || max_priority_fee_per_gas.is_none()
+                    || max_fee_per_blob_gas.is_none()
+            }
+        };
+        if !needs_fees {
+            return Ok(call);
+        }
+
+        // Explicitly typed legacy and EIP-2930 calls are priced without fee history.
+        if let MessageCall::Legacy {
+            tag: Some(_),
+            gas_price,
+            ..
+        }
+        | MessageCall::EIP2930 {
+            tag: Some(_),
+            gas_price,
+            ..
+        } = &mut call
+        {
+            *gas_price = Some(client.gas_price().await?);
+            return Ok(call);
+        }
+
+        let history = client
+            .fee_history(
+                self.fee_history_blocks.into(),
+                BlockNumber::Latest,
+                vec![self.reward_percentile],
+            )
+            .await?;
+        // Nodes report zero base fees for blocks before London.
+        let next_base_fee = history
+            .base_fee_per_gas
+            .last()
+            .copied()
+            .filter(|base_fee| *base_fee > 0);
+
+        let mut call = match (call, next_base_fee) {
+            (
+                MessageCall::Legacy {
+                    tag: None,
+                    from,
+                    to,
+                    gas,
+                    value,
+                    data,
+                    ..
+                },
+                Some(_),
+            ) => MessageCall::EIP1559 {
+                tag: None,
+                from,
+                to,
+                gas,
+                max_fee_per_gas: None,
+                max_priority_fee_per_gas: None,
+                value,
+                data,
+                access_list: None,
+            },
+            (
+                MessageCall::EIP2930 {
+                    tag: None,
+                    from,
+                    to,
+                    gas,
+                    value,
+                    data,
+                    access_list,
+                    ..
+                },
+                Some(_),
+            ) => MessageCall::EIP1559 {
+                tag: None,
+                from,
+                to,
+                gas,
+                max_fee_per_gas: None,
+                max_priority_fee_per_gas: None,
+                value,
+                data,
+                access_list,
+            },
+            (call, _) => call,
+        };
+
+        match &mut call {
+            MessageCall::Legacy { gas_price, .. } | MessageCall::EIP2930 { gas_price, .. } => {
+                *gas_price = Some(client.gas_price().await?);
+            }
+            MessageCall::EIP1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                ..
+            }
+            | MessageCall::EIP4844 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                ..
+            }
+            | MessageCall::EIP7702 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                ..
+            } => {
+                let (next_base_fee, estimate) =
+                    next_base_fee.zip(history.estimate_fees(0)).ok_or_else(|| {
+                        jsonrpsee::core::Error::Custom(
+                            "fee history has no base fee to price an EIP-1559 transaction".into(),
+                        )
+                    })?;
+                let priority_fee =
+                    *max_priority_fee_per_gas.get_or_insert(estimate.max_priority_fee_per_gas);
+                // Derived from the priority fee actually used, so a high one set by the caller
+                // does not end up above the max fee.
+                max_fee_per_gas.get_or_insert(
+                    FeeEstimate::with_priority_fee(next_base_fee, priority_fee).max_fee_per_gas,
+                );
+            }
+        }
+        if let MessageCall::EIP4844 {
+            max_fee_per_blob_gas: max_fee_per_blob_gas @ None,
+            ..
+        } = &mut call
+        {
+            let blob_base_fee = history
+                .base_fee_per_blob_gas
+                .as_ref()
+                .and_then(|fees| fees.last().copied())
+                .ok_or_else(|| {
+                    jsonrpsee::core::Error::Custom("fee history has no blob base fee".into())
+                })?;
+            // Leave room for the blob base fee of the next block to double.
+            *max_fee_per_blob_gas = Some(blob_base_fee.saturating_mul(U256::new(2)));
+        }
+        Ok(call)
+    }
+}
+
+#[cfg(feature = "client")]
+fn gas_mut(call: &mut MessageCall) -> &mut Option<U64> {
+    match call {
+        MessageCall::Legacy { gas, .. }
+        | MessageCall::EIP2930 { gas, .. }
+        | MessageCall::EIP1559 { gas, .. }
+        | MessageCall::EIP4844 { gas, .. }
+        | MessageCall::EIP7702 { gas, .. } => gas,
+    }
+}
+
+#[cfg(feature = "client")]
+fn missing_field(name: &str) -> jsonrpsee::core::Error {
+    jsonrpsee::core::Error::Custom(format!("transaction filler requires `{name}`"))
+}
+
+/// Convert a call with gas and fees filled in into a transaction message.
+#[cfg(feature = "client")]
+fn into_transaction_message(
+    call: MessageCall,
+    chain_id: U64,
+    nonce: U64,
+) -> Result<TransactionMessage, jsonrpsee::core::Error> {
+    let gas = |gas: Option<U64>| gas.ok_or_else(|| missing_field("gas"));
+    let fee = |fee: Option<U256>, name| fee.ok_or_else(|| missing_field(name));
+
+    Ok(match call {
+        MessageCall::Legacy {
+            to,
+            gas: call_gas,
+            gas_price,
+            value,
+            data,
+            ..
+        } => TransactionMessage::Legacy {
+            chain_id: Some(chain_id),
+            nonce,
+            to,
+            gas: gas(call_gas)?,
+            gas_price: fee(gas_price, "gasPrice")?,
+            value: value.unwrap_or_default(),
+            input: data.unwrap_or_default(),
+        },
+        MessageCall::EIP2930 {
+            to,
+            gas: call_gas,
+            gas_price,
+            value,
+            data,
+            access_list,
+            ..
+        } => TransactionMessage::EIP2930 {
+            chain_id,
+            nonce,
+            to,
+            gas: gas(call_gas)?,
+            gas_price: fee(gas_price, "gasPrice")?,
+            value: value.unwrap_or_default(),
+            input: data.unwrap_or_default(),
+            access_list: access_list.unwrap_or_default(),
+        },
+        MessageCall::EIP1559 {
+            to,
+            gas: call_gas,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            value,
+            data,
+            access_list,
+            ..
+        } => TransactionMessage::EIP1559 {
+            chain_id,
+            nonce,
+            to,
+            gas: gas(call_gas)?,
+            max_fee_per_gas: fee(max_fee_per_gas, "maxFeePerGas")?,
+            max_priority_fee_per_gas: fee(max_priority_fee_per_gas, "maxPriorityFeePerGas")?,
+            value: value.unwrap_or_default(),
+            input: data.unwrap_or_default(),
+            access_list: access_list.unwrap_or_default(),
+        },
+        MessageCall::EIP4844 {
+            to,
+            gas: call_gas,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            max_fee_per_blob_gas,
+            value,
+            data,
+            access_list,
+            blob_versioned_hashes,
+            ..
+        } => TransactionMessage::EIP4844 {
+            chain_id,
+            nonce,
+            to: to.ok_or_else(|| missing_field("to"))?,
+            gas: gas(call_gas)?,
+            max_fee_per_gas: fee(max_fee_per_gas, "maxFeePerGas")?,
+            max_priority_fee_per_gas: fee(max_priority_fee_per_gas, "maxPriorityFeePerGas")?,
+            max_fee_per_blob_gas: fee(max_fee_per_blob_gas, "maxFeePerBlobGas")?,
+            value: value.unwrap_or_default(),
+            input: data.unwrap_or_default(),
+            access_list: access_list.unwrap_or_default(),
+            blob_versioned_hashes,
+        },
+        MessageCall::EIP7702 {
+            to,
+            gas: call_gas,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            value,
+            data,
+            access_list,
+            authorization_list,
+            ..
+        } => TransactionMessage::EIP7702 {
+            chain_id,
+            nonce,
+            to: to.ok_or_else(|| missing_field("to"))?,
+            gas: gas(call_gas)?,
+            max_fee_per_gas: fee(max_fee_per_gas, "maxFeePerGas")?,
+            max_priority_fee_per_gas: fee(max_priority_fee_per_gas, "maxPriorityFeePerGas")?,
+            value: value.unwrap_or_default(),
+            input: data.unwrap_or_default(),
+            access_list: access_list.unwrap_or_default(),
+            authorization_list,
+        },
+    })
 }
 
 #[cfg(test)]
@@ -259,4 +1407,1054 @@ mod tests {
 
         assert_eq!(serde_json::from_value::<LogFilter>(encoded).unwrap(), v);
     }
+
+    #[test]
+    fn log_filter_matches() {
+        let token = Address::from(hex!("dac17f958d2ee523a2206206994597c13d831ec7"));
+        let approval = H256(hex!(
+            "8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925"
+        ));
+        let transfer = H256(hex!(
+            "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+        ));
+        let owner = H256(hex!(
+            "0000000000000000000000009a53bfba35269414f3b2d20b52ca01b15932c7b2"
+        ));
+        let log = TransactionLog {
+            log_index: Some(0x118.into()),
+            transaction_index: Some(0x7f.into()),
+            transaction_hash: None,
+            block_hash: Some(H256::repeat_byte(0x4a)),
+            block_number: Some(0x129f4b9.into()),
+            block_timestamp: None,
+            address: token,
+            data: Bytes::default(),
+            topics: vec![approval, owner, H256::repeat_byte(0x39)],
+            removed: false,
+        };
+        let bloom = logs_bloom([&log]);
+
+        let filter = |address: Vec<Address>, topics: Vec<Option<Vec<H256>>>| LogFilter {
+            block_filter: None,
+            address: Some(LogAddressFilter(address)),
+            topics: Some(topics.into_iter().map(|t| t.map(LogTopicFilter)).collect()),
+        };
+
+        for (filter, matches) in [
+            (filter(vec![], vec![]), true),
+            (filter(vec![Address::zero(), token], vec![]), true),
+            (filter(vec![Address::zero()], vec![]), false),
+            (filter(vec![], vec![Some(vec![transfer, approval])]), true),
+            (filter(vec![], vec![Some(vec![transfer])]), false),
+            (filter(vec![], vec![None, Some(vec![owner])]), true),
+            (
+                filter(vec![], vec![Some(vec![]), Some(vec![approval])]),
+                false,
+            ),
+            (filter(vec![token], vec![None, None, None, None]), false),
+        ] {
+            assert_eq!(filter.matches(&log), matches, "{filter:?}");
+            if matches {
+                assert!(filter.may_match(&bloom), "{filter:?}");
+            }
+        }
+        assert!(!filter(vec![Address::zero()], vec![]).may_match(&bloom));
+        assert!(!filter(vec![], vec![Some(vec![transfer])]).may_match(&bloom));
+        // Bloom checks do not consider topic positions.
+        assert!(filter(vec![], vec![Some(vec![owner])]).may_match(&bloom));
+
+        let mut by_block = filter(vec![token], vec![]);
+        by_block.block_filter = Some(BlockFilter::Bounded {
+            from_block: Some(BlockNumber::Number(0x129f4b9.into())),
+            to_block: Some(BlockNumber::Latest),
+        });
+        assert!(by_block.matches(&log));
+        by_block.block_filter = Some(BlockFilter::Bounded {
+            from_block: Some(BlockNumber::Earliest),
+            to_block: Some(BlockNumber::Number(0x129f4b8.into())),
+        });
+        assert!(!by_block.matches(&log));
+        by_block.block_filter = Some(BlockFilter::Exact {
+            block_hash: H256::repeat_byte(0x4a),
+        });
+        assert!(by_block.matches(&log));
+    }
+
+    #[test]
+    fn filter_changes_deserialize() {
+        assert_eq!(
+            serde_json::from_value::<FilterChanges>(json!([])).unwrap(),
+            FilterChanges::Hashes(vec![])
+        );
+        assert_eq!(
+            serde_json::from_value::<FilterChanges>(json!([
+                "0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+            ]))
+            .unwrap(),
+            FilterChanges::Hashes(vec![hex!(
+                "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+            )
+            .into()])
+        );
+
+        let logs = json!([{
+            "address": "0xdeadbeef00000000000000000000000000000000",
+            "topics": ["0xaa00000000000000000000000000000000000000000000000000000000000000"],
+            "data": "0x01",
+            "blockNumber": "0x1",
+            "transactionHash": "0xbb00000000000000000000000000000000000000000000000000000000000000",
+            "transactionIndex": "0x0",
+            "blockHash": "0xcc00000000000000000000000000000000000000000000000000000000000000",
+            "logIndex": "0x2",
+            "removed": false,
+        }]);
+        let v = FilterChanges::Logs(vec![TransactionLog {
+            log_index: Some(2_u64.into()),
+            transaction_index: Some(0_u64.into()),
+            transaction_hash: Some(
+                hex!("bb00000000000000000000000000000000000000000000000000000000000000").into(),
+            ),
+            block_hash: Some(
+                hex!("cc00000000000000000000000000000000000000000000000000000000000000").into(),
+            ),
+            block_number: Some(1_u64.into()),
+            block_timestamp: None,
+            address: hex!("deadbeef00000000000000000000000000000000").into(),
+            data: hex!("01").to_vec().into(),
+            topics: vec![
+                hex!("aa00000000000000000000000000000000000000000000000000000000000000").into(),
+            ],
+            removed: false,
+        }]);
+        assert_eq!(
+            serde_json::from_value::<FilterChanges>(logs.clone()).unwrap(),
+            v
+        );
+        assert_eq!(serde_json::to_value(&v).unwrap(), logs);
+
+        assert_eq!(
+            serde_json::from_value::<FilterId>(json!("0x0f3e6a1b")).unwrap(),
+            FilterId("0x0f3e6a1b".into())
+        );
+    }
+
+    #[test]
+    fn fee_history() {
+        let encoded = json!({
+            "oldestBlock": "0x10b52f",
+            "baseFeePerGas": ["0x3da8e7618", "0x3e1ba3b1b", "0x3dfd72b90", "0x3d64eee76"],
+            "gasUsedRatio": [0.529, 0.4884, 0.4229],
+            "reward": [
+                ["0x59682f00", "0x9502f900"],
+                ["0x0", "0x3b9aca00"],
+                ["0x77359400", "0xb2d05e00"],
+            ],
+            "baseFeePerBlobGas": ["0x1", "0x1", "0x1", "0x1"],
+            "blobGasUsedRatio": [0.0, 0.5, 1.0],
+        });
+        let v = serde_json::from_value::<FeeHistory>(encoded.clone()).unwrap();
+        assert_eq!(v.oldest_block, 0x10b52f.into());
+        assert_eq!(v.base_fee_per_gas.len(), 4);
+        assert_eq!(v.blob_gas_used_ratio.as_deref(), Some(&[0.0, 0.5, 1.0][..]));
+        assert_eq!(serde_json::to_value(&v).unwrap(), encoded);
+
+        // Zero reward is skipped, median of 1.5 and 2 gwei.
+        let estimate = v.estimate_fees(0).unwrap();
+        assert_eq!(
+            estimate.max_priority_fee_per_gas,
+            1_750_000_000_u64.as_u256()
+        );
+        assert_eq!(
+            estimate.max_fee_per_gas,
+            0x3d64eee76_u64.as_u256() * 2 + 1_750_000_000
+        );
+        assert_eq!(
+            v.estimate_fees(1).unwrap().max_priority_fee_per_gas,
+            2_500_000_000_u64.as_u256()
+        );
+
+        let mut call = MessageCall::EIP1559 {
+            tag: None,
+            from: None,
+            to: None,
+            gas: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            value: None,
+            data: None,
+            access_list: None,
+        };
+        estimate.apply(&mut call);
+        assert!(matches!(
+            call,
+            MessageCall::EIP1559 {
+                max_fee_per_gas: Some(_),
+                max_priority_fee_per_gas: Some(_),
+                ..
+            }
+        ));
+
+        let empty = FeeHistory {
+            oldest_block: 0_u64.into(),
+            base_fee_per_gas: vec![7.as_u256()],
+            gas_used_ratio: vec![],
+            reward: None,
+            base_fee_per_blob_gas: None,
+            blob_gas_used_ratio: None,
+        };
+        assert_eq!(
+            empty.estimate_fees(0),
+            Some(FeeEstimate {
+                max_fee_per_gas: 14 + FeeEstimate::DEFAULT_PRIORITY_FEE,
+                max_priority_fee_per_gas: FeeEstimate::DEFAULT_PRIORITY_FEE,
+            })
+        );
+
+        let huge = FeeHistory {
+            base_fee_per_gas: vec![U256::MAX],
+            reward: Some(vec![vec![U256::MAX - 1], vec![U256::MAX]]),
+            ..empty
+        };
+        assert_eq!(
+            huge.estimate_fees(0),
+            Some(FeeEstimate {
+                max_fee_per_gas: U256::MAX,
+                max_priority_fee_per_gas: U256::MAX - 1,
+            })
+        );
+    }
+
+    #[test]
+    fn access_list_result() {
+        let encoded = json!({
+            "accessList": [
+                {
+                    "address": "0xa02457e5dfd32bda5fc7e1f1b008aa5979568150",
+                    "storageKeys": [
+                        "0x0000000000000000000000000000000000000000000000000000000000000081",
+                    ],
+                },
+            ],
+            "gasUsed": "0x125f8",
+        });
+        let v = AccessListResult {
+            access_list: vec![AccessListEntry {
+                address: hex!("a02457e5dfd32bda5fc7e1f1b008aa5979568150").into(),
+                storage_keys: vec![H256::from_low_u64_be(0x81)],
+            }],
+            gas_used: 0x125f8.into(),
+            error: None,
+        };
+        assert_eq!(
+            serde_json::from_value::<AccessListResult>(encoded.clone()).unwrap(),
+            v
+        );
+        assert_eq!(serde_json::to_value(&v).unwrap(), encoded);
+
+        assert_eq!(
+            serde_json::from_value::<AccessListResult>(json!({
+                "accessList": [],
+                "gasUsed": "0x5208",
+                "error": "execution reverted",
+            }))
+            .unwrap()
+            .error
+            .as_deref(),
+            Some("execution reverted")
+        );
+    }
+
+    #[test]
+    fn overrides_serialize() {
+        let state_override = StateOverride::from([
+            (
+                hex!("deadbeef00000000000000000000000000000000").into(),
+                AccountOverride {
+                    balance: Some(0xde0b6b3a7640000_u64.into()),
+                    code: Some(hex!("6080").to_vec().into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                hex!("c0ffee0000000000000000000000000000000000").into(),
+                AccountOverride {
+                    nonce: Some(5.into()),
+                    state_diff: Some(BTreeMap::from([(
+                        H256::from_low_u64_be(1),
+                        H256::from_low_u64_be(2),
+                    )])),
+                    ..Default::default()
+                },
+            ),
+        ]);
+        let encoded = json!({
+            "0xdeadbeef00000000000000000000000000000000": {
+                "balance": "0xde0b6b3a7640000",
+                "code": "0x6080",
+            },
+            "0xc0ffee0000000000000000000000000000000000": {
+                "nonce": "0x5",
+                "stateDiff": {
+                    "0x0000000000000000000000000000000000000000000000000000000000000001":
+                        "0x0000000000000000000000000000000000000000000000000000000000000002",
+                },
+            },
+        });
+        assert_eq!(serde_json::to_value(&state_override).unwrap(), encoded);
+        assert_eq!(
+            serde_json::from_value::<StateOverride>(encoded).unwrap(),
+            state_override
+        );
+
+        let block_overrides = BlockOverrides {
+            number: Some(0x10.into()),
+            coinbase: Some(Address::repeat_byte(0xAA)),
+            base_fee: Some(7.as_u256()),
+            ..Default::default()
+        };
+        let encoded = json!({
+            "number": "0x10",
+            "feeRecipient": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "baseFeePerGas": "0x7",
+        });
+        assert_eq!(serde_json::to_value(&block_overrides).unwrap(), encoded);
+        assert_eq!(
+            serde_json::from_value::<BlockOverrides>(encoded).unwrap(),
+            block_overrides
+        );
+        assert_eq!(
+            serde_json::from_value::<BlockOverrides>(json!({
+                "coinbase": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+                "random": "0x0000000000000000000000000000000000000000000000000000000000000001",
+                "baseFee": "0x7",
+            }))
+            .unwrap(),
+            BlockOverrides {
+                coinbase: Some(Address::repeat_byte(0xAA)),
+                random: Some(H256::from_low_u64_be(1)),
+                base_fee: Some(7.as_u256()),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(BlockOverrides::default()).unwrap(),
+            json!({})
+        );
+    }
+
+    /// Handler of a [`mock_node`] method, from the request params to the result.
+    #[cfg(all(feature = "client", feature = "server"))]
+    type MockMethod = fn(serde_json::Value) -> Result<serde_json::Value, jsonrpsee::core::Error>;
+
+    /// Requests received by a [`mock_node`], as method and params.
+    #[cfg(all(feature = "client", feature = "server"))]
+    type MockRequests = std::sync::Arc<std::sync::Mutex<Vec<(&'static str, serde_json::Value)>>>;
+
+    /// Start a local node serving `methods`, returning a client to it, the handle to stop it and
+    /// the requests it receives.
+    #[cfg(all(feature = "client", feature = "server"))]
+    async fn mock_node(
+        methods: &[(&'static str, MockMethod)],
+    ) -> (
+        jsonrpsee::http_client::HttpClient,
+        jsonrpsee::server::ServerHandle,
+        MockRequests,
+    ) {
+        use jsonrpsee::{http_client::HttpClientBuilder, server::ServerBuilder, RpcModule};
+
+        let requests = MockRequests::default();
+        let mut module = RpcModule::new(requests.clone());
+        for &(method, handler) in methods {
+            module
+                .register_method(method, move |params, requests| {
+                    let params = params.parse::<serde_json::Value>()?;
+                    requests.lock().unwrap().push((method, params.clone()));
+                    handler(params)
+                })
+                .unwrap();
+        }
+
+        let server = ServerBuilder::default().build("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", server.local_addr().unwrap());
+        let handle = server.start(module).unwrap();
+        let client = HttpClientBuilder::default().build(url).unwrap();
+        (client, handle, requests)
+    }
+
+    #[cfg(all(feature = "client", feature = "server"))]
+    #[tokio::test]
+    async fn call_with_overrides_params() {
+        let (client, handle, requests) = mock_node(&[
+            ("eth_call", |_| Ok(json!("0x"))),
+            ("eth_estimateGas", |_| Ok(json!("0x5208"))),
+        ])
+        .await;
+
+        let call = serde_json::from_value::<MessageCall>(json!({
+            "to": "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+        }))
+        .unwrap();
+        let state_override = StateOverride::from([(
+            Address::repeat_byte(0xAA),
+            AccountOverride {
+                balance: Some(1.as_u256()),
+                ..Default::default()
+            },
+        )]);
+        let block_overrides = BlockOverrides {
+            number: Some(0x10.into()),
+            ..Default::default()
+        };
+        let latest = BlockId::from(BlockNumber::Latest);
+
+        client
+            .call_with_overrides(call.clone(), latest, None, None)
+            .await
+            .unwrap();
+        client
+            .call_with_overrides(call.clone(), latest, Some(state_override.clone()), None)
+            .await
+            .unwrap();
+        client
+            .call_with_overrides(
+                call.clone(),
+                latest,
+                Some(state_override),
+                Some(block_overrides.clone()),
+            )
+            .await
+            .unwrap();
+        client
+            .estimate_gas_with_overrides(call.clone(), latest, None, Some(block_overrides))
+            .await
+            .unwrap();
+        client.estimate_gas(call, latest, None).await.unwrap();
+
+        let call = json!({ "to": "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb" });
+        let state = json!({ "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa": { "balance": "0x1" } });
+        let block = json!({ "number": "0x10" });
+        assert_eq!(
+            requests
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(_, params)| params.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                json!([call, "latest"]),
+                json!([call, "latest", state]),
+                json!([call, "latest", state, block]),
+                json!([call, "latest", null, block]),
+                json!([call, "latest", null]),
+            ]
+        );
+
+        handle.stop().unwrap();
+    }
+
+    #[test]
+    fn simulate_payload_serialize() {
+        let payload = SimulatePayload {
+            block_state_calls: vec![SimBlock {
+                block_overrides: Some(BlockOverrides {
+                    number: Some(0x1000.into()),
+                    ..Default::default()
+                }),
+                state_overrides: Some(StateOverride::from([(
+                    Address::repeat_byte(0xAA),
+                    AccountOverride {
+                        balance: Some(0xde0b6b3a7640000_u64.into()),
+                        ..Default::default()
+                    },
+                )])),
+                calls: vec![MessageCall::Legacy {
+                    tag: None,
+                    from: Some(Address::repeat_byte(0xAA)),
+                    to: Some(Address::repeat_byte(0xBB)),
+                    gas: None,
+                    gas_price: None,
+                    value: Some(1.as_u256()),
+                    data: None,
+                }],
+            }],
+            trace_transfers: true,
+            validation: false,
+            return_full_transactions: false,
+        };
+        let encoded = json!({
+            "blockStateCalls": [{
+                "blockOverrides": { "number": "0x1000" },
+                "stateOverrides": {
+                    "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa": { "balance": "0xde0b6b3a7640000" },
+                },
+                "calls": [{
+                    "from": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+                    "to": "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+                    "value": "0x1",
+                }],
+            }],
+            "traceTransfers": true,
+        });
+        assert_eq!(serde_json::to_value(&payload).unwrap(), encoded);
+        assert_eq!(
+            serde_json::from_value::<SimulatePayload>(encoded).unwrap(),
+            payload
+        );
+    }
+
+    #[test]
+    fn simulated_block_deserialize() {
+        let encoded = json!({
+            "number": "0x1000",
+            "hash": "0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3",
+            "parentHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "transactionsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "stateRoot": "0xd7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544",
+            "receiptsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "miner": "0x0000000000000000000000000000000000000000",
+            "difficulty": "0x0",
+            "extraData": "0x",
+            "size": "0x29a",
+            "gasLimit": "0x1c9c380",
+            "gasUsed": "0x5208",
+            "timestamp": "0x64",
+            "transactions": ["0xbb00000000000000000000000000000000000000000000000000000000000000"],
+            "uncles": [],
+            "baseFeePerGas": "0x7",
+            "calls": [{
+                "returnData": "0x",
+                "logs": [{
+                    "address": "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee",
+                    "topics": ["0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"],
+                    "data": "0x0000000000000000000000000000000000000000000000000000000000000001",
+                    "blockNumber": "0x1000",
+                    "transactionHash": "0xbb00000000000000000000000000000000000000000000000000000000000000",
+                    "transactionIndex": "0x0",
+                    "blockHash": "0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3",
+                    "logIndex": "0x0",
+                }],
+                "gasUsed": "0x5208",
+                "status": "0x1",
+            }, {
+                "returnData": "0x08c379a0",
+                "logs": [],
+                "gasUsed": "0x6000",
+                "status": "0x0",
+                "error": { "code": 3, "message": "execution reverted", "data": "0x08c379a0" },
+            }],
+        });
+        let v = serde_json::from_value::<SimulatedBlock>(encoded).unwrap();
+        assert_eq!(v.inner.number, Some(0x1000.into()));
+        assert_eq!(v.calls.len(), 2);
+        assert_eq!(v.calls[0].logs.len(), 1);
+        assert_eq!(
+            v.calls[1].error,
+            Some(SimCallError {
+                code: 3,
+                message: "execution reverted".into(),
+                data: Some(hex!("08c379a0").to_vec().into()),
+            })
+        );
+    }
+
+    #[test]
+    fn block_receipts_deserialize() {
+        assert_eq!(
+            serde_json::from_value::<Option<Vec<TransactionReceipt>>>(json!(null)).unwrap(),
+            None
+        );
+
+        let encoded = json!([{
+            "transactionHash": "0xbb00000000000000000000000000000000000000000000000000000000000000",
+            "transactionIndex": "0x0",
+            "blockHash": "0xcc00000000000000000000000000000000000000000000000000000000000000",
+            "blockNumber": "0x1",
+            "from": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "to": "0xdeadbeef00000000000000000000000000000000",
+            "cumulativeGasUsed": "0x5208",
+            "gasUsed": "0x5208",
+            "contractAddress": null,
+            "logs": [{
+                "address": "0xdeadbeef00000000000000000000000000000000",
+                "topics": ["0xaa00000000000000000000000000000000000000000000000000000000000000"],
+                "data": "0x",
+                "blockNumber": "0x1",
+                "transactionHash": "0xbb00000000000000000000000000000000000000000000000000000000000000",
+                "transactionIndex": "0x0",
+                "blockHash": "0xcc00000000000000000000000000000000000000000000000000000000000000",
+                "logIndex": "0x0",
+            }],
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "status": "0x1",
+        }, {
+            "transactionHash": "0xdd00000000000000000000000000000000000000000000000000000000000000",
+            "transactionIndex": "0x1",
+            "blockHash": "0xcc00000000000000000000000000000000000000000000000000000000000000",
+            "blockNumber": "0x1",
+            "from": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "to": null,
+            "cumulativeGasUsed": "0x1d4c0",
+            "gasUsed": "0x186b8",
+            "contractAddress": "0xc0ffee0000000000000000000000000000000000",
+            "logs": [],
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "status": "0x0",
+            "blobGasUsed": "0x20000",
+            "blobGasPrice": "0x1",
+        }]);
+        let receipts = serde_json::from_value::<Option<Vec<TransactionReceipt>>>(encoded)
+            .unwrap()
+            .unwrap();
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(receipts[0].logs.len(), 1);
+        assert_eq!(receipts[0].blob_gas_used, None);
+        assert_eq!(receipts[1].blob_gas_used, Some(0x20000.into()));
+        assert_eq!(receipts[1].blob_gas_price, Some(1.as_u256()));
+        assert_eq!(receipts[1].to, None);
+        assert_eq!(
+            receipts[1].contract_address,
+            Some(hex!("c0ffee0000000000000000000000000000000000").into())
+        );
+    }
+
+    #[test]
+    fn subscription_params_serialize() {
+        assert_eq!(
+            serde_json::to_value(SubscriptionKind::NewPendingTransactions).unwrap(),
+            json!("newPendingTransactions")
+        );
+        assert_eq!(
+            serde_json::to_value(SubscriptionParams::FullTransactions(true)).unwrap(),
+            json!(true)
+        );
+        assert_eq!(
+            serde_json::from_value::<SubscriptionParams>(json!({
+                "address": "0xdeadbeef00000000000000000000000000000000",
+            }))
+            .unwrap(),
+            SubscriptionParams::Logs(Box::new(LogFilter {
+                block_filter: Some(BlockFilter::Bounded {
+                    from_block: None,
+                    to_block: None,
+                }),
+                address: Some(LogAddressFilter(vec![hex!(
+                    "deadbeef00000000000000000000000000000000"
+                )
+                .into()])),
+                topics: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn syncing_notification() {
+        let messages: Vec<serde_json::Value> =
+            serde_json::from_str(include_str!("../testdata/syncing.json")).unwrap();
+        let notifications: Vec<SyncingNotification> = messages
+            .iter()
+            .map(|m| serde_json::from_value(m["params"]["result"].clone()).unwrap())
+            .collect();
+        let progress = SyncProgress {
+            starting_block: 19526741.into(),
+            current_block: 19526790.into(),
+            highest_block: 19526841.into(),
+        };
+        assert_eq!(
+            notifications,
+            vec![
+                SyncingNotification::Syncing(progress),
+                SyncingNotification::NotSyncing
+            ]
+        );
+        assert!(matches!(
+            serde_json::from_value::<PubSubItem>(messages[0]["params"]["result"].clone()).unwrap(),
+            PubSubItem::Syncing(SyncingNotification::Syncing(_))
+        ));
+
+        let camel_case = json!({
+            "syncing": true,
+            "status": {
+                "startingBlock": "0x129f455",
+                "currentBlock": "0x129f486",
+                "highestBlock": "0x129f4b9",
+            },
+        });
+        assert_eq!(
+            serde_json::to_value(SyncingNotification::Syncing(progress)).unwrap(),
+            camel_case
+        );
+        assert_eq!(
+            serde_json::from_value::<SyncingNotification>(camel_case).unwrap(),
+            SyncingNotification::Syncing(progress)
+        );
+        assert_eq!(
+            serde_json::from_value::<SyncingNotification>(json!({ "syncing": false })).unwrap(),
+            SyncingNotification::NotSyncing
+        );
+        assert!(serde_json::from_value::<SyncingNotification>(json!(true)).is_err());
+        assert!(serde_json::from_value::<SyncingNotification>(json!({ "syncing": true })).is_err());
+    }
+
+    #[test]
+    fn pubsub_item_deserialize() {
+        assert_eq!(
+            serde_json::from_value::<PubSubItem>(json!(
+                "0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+            ))
+            .unwrap(),
+            PubSubItem::TransactionHash(
+                hex!("d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3").into()
+            )
+        );
+        assert_eq!(
+            serde_json::from_value::<PubSubItem>(json!(false)).unwrap(),
+            PubSubItem::Syncing(SyncingNotification::NotSyncing)
+        );
+
+        let log = json!({
+            "address": "0xdeadbeef00000000000000000000000000000000",
+            "topics": ["0xaa00000000000000000000000000000000000000000000000000000000000000"],
+            "data": "0x",
+            "blockNumber": "0x1",
+            "transactionHash": "0xbb00000000000000000000000000000000000000000000000000000000000000",
+            "transactionIndex": "0x0",
+            "blockHash": "0xcc00000000000000000000000000000000000000000000000000000000000000",
+            "logIndex": "0x0",
+        });
+        assert!(matches!(
+            serde_json::from_value::<PubSubItem>(log).unwrap(),
+            PubSubItem::Log(_)
+        ));
+
+        let header = json!({
+            "parentHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+            "miner": "0x0000000000000000000000000000000000000000",
+            "stateRoot": "0xd7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544",
+            "transactionsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "receiptsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "difficulty": "0x400000000",
+            "number": "0x0",
+            "gasLimit": "0x1388",
+            "gasUsed": "0x0",
+            "timestamp": "0x0",
+            "extraData": "0x11bbe8db4e347b4e8c937c1c8370e4b5ed33adb3db69cbdb7a38e1e50b1b82fa",
+            "mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "nonce": "0x0000000000000042",
+            "hash": "0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3",
+        });
+        assert!(matches!(
+            serde_json::from_value::<PubSubItem>(header).unwrap(),
+            PubSubItem::Header(_)
+        ));
+    }
+
+    #[cfg(all(feature = "client", feature = "server"))]
+    #[tokio::test]
+    async fn get_logs_chunked_splits_range() {
+        use futures::TryStreamExt;
+        use jsonrpsee::{
+            core::Error,
+            types::error::{CallError, ErrorObject},
+        };
+
+        fn bounds(params: &serde_json::Value) -> (u64, u64) {
+            let (filter,): (LogFilter,) = serde_json::from_value(params.clone()).unwrap();
+            let Some(BlockFilter::Bounded {
+                from_block: Some(BlockNumber::Number(from)),
+                to_block: Some(BlockNumber::Number(to)),
+            }) = filter.block_filter
+            else {
+                panic!("unbounded filter {params}");
+            };
+            (from.as_u64(), to.as_u64())
+        }
+
+        let (client, handle, requests) = mock_node(&[
+            ("eth_blockNumber", |_| Ok(json!("0x14"))),
+            ("eth_getLogs", |params| {
+                let (from, to) = bounds(&params);
+                // Blocks 8 to 11 are dense, other blocks have a log every third block.
+                let blocks = (from..=to)
+                    .filter(|n| (8..12).contains(n) || n % 3 == 0)
+                    .collect::<Vec<_>>();
+                if blocks.len() > 2 {
+                    return Err(Error::Call(CallError::Custom(ErrorObject::owned(
+                        -32005,
+                        "query returned more than 10000 results",
+                        None::<()>,
+                    ))));
+                }
+                let logs = blocks
+                    .into_iter()
+                    .map(|n| TransactionLog {
+                        log_index: Some(0.into()),
+                        transaction_index: Some(0.into()),
+                        transaction_hash: None,
+                        block_hash: Some(H256::from_low_u64_be(n)),
+                        block_number: Some(n.into()),
+                        block_timestamp: None,
+                        address: Address::zero(),
+                        data: Bytes::default(),
+                        topics: vec![],
+                        removed: false,
+                    })
+                    .collect::<Vec<_>>();
+                Ok(serde_json::to_value(logs)?)
+            }),
+        ])
+        .await;
+
+        let filter = |from_block, to_block| LogFilter {
+            block_filter: Some(BlockFilter::Bounded {
+                from_block,
+                to_block,
+            }),
+            address: None,
+            topics: None,
+        };
+
+        let logs = get_logs_chunked(
+            &client,
+            filter(Some(BlockNumber::Number(1.into())), None),
+            6,
+        )
+        .map_ok(|log| log.block_number.unwrap().as_u64())
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+        assert_eq!(logs, vec![3, 6, 8, 9, 10, 11, 12, 15, 18]);
+
+        let requests = std::mem::take(&mut *requests.lock().unwrap())
+            .into_iter()
+            .filter(|(method, _)| *method == "eth_getLogs")
+            .map(|(_, params)| bounds(&params))
+            .collect::<Vec<_>>();
+        assert_eq!(requests.first(), Some(&(1, 6)));
+        assert_eq!(requests.last().unwrap().1, 20);
+        assert!(requests.iter().all(|(from, to)| to - from < 6));
+
+        let result = get_logs_chunked(&client, filter(Some(BlockNumber::Safe), None), 6)
+            .try_collect::<Vec<_>>()
+            .await;
+        assert!(matches!(result, Err(Error::Call(_))), "{result:?}");
+
+        let mut exact = filter(None, None);
+        exact.block_filter = Some(BlockFilter::Exact {
+            block_hash: H256::zero(),
+        });
+        let result = get_logs_chunked(&client, exact, 6)
+            .try_collect::<Vec<_>>()
+            .await;
+        assert!(result.is_err());
+
+        handle.stop().unwrap();
+    }
+
+    #[cfg(all(feature = "client", feature = "server"))]
+    #[tokio::test]
+    async fn transaction_filler() {
+        const GWEI: u128 = 1_000_000_000;
+
+        fn fee_history(base_fee: u128) -> Result<serde_json::Value, jsonrpsee::core::Error> {
+            Ok(serde_json::to_value(FeeHistory {
+                oldest_block: 100.into(),
+                base_fee_per_gas: vec![U256::new(base_fee); 2],
+                gas_used_ratio: vec![0.5],
+                reward: Some(vec![vec![U256::new(2 * GWEI)]]),
+                base_fee_per_blob_gas: Some(vec![U256::new(1), U256::new(3)]),
+                blob_gas_used_ratio: Some(vec![0.5]),
+            })?)
+        }
+
+        fn node(fee_history: MockMethod) -> [(&'static str, MockMethod); 6] {
+            [
+                ("eth_chainId", |_| Ok(json!("0x1"))),
+                ("eth_getTransactionCount", |params| {
+                    assert_eq!(params[1], "pending");
+                    Ok(json!("0x7"))
+                }),
+                ("eth_estimateGas", |params| {
+                    // Only the call and block, no trailing `null` overrides.
+                    assert_eq!(params.as_array().unwrap().len(), 2);
+                    Ok(json!("0x5208"))
+                }),
+                ("eth_getBlockByNumber", |_| {
+                    // Gas limit of 30M.
+                    let headers: serde_json::Value =
+                        serde_json::from_str(include_str!("../testdata/headers.json")).unwrap();
+                    let mut block = json!({
+                        "totalDifficulty": null,
+                        "sealFields": null,
+                        "size": "0x0",
+                        "transactions": [],
+                        "uncles": [],
+                    });
+                    let object = block.as_object_mut().unwrap();
+                    object.extend(headers["cancun"].as_object().unwrap().clone());
+                    Ok(block)
+                }),
+                ("eth_gasPrice", |_| {
+                    Ok(serde_json::to_value(U256::new(5 * GWEI))?)
+                }),
+                ("eth_feeHistory", fee_history),
+            ]
+        }
+
+        let (client, handle, requests) = mock_node(&node(|_| fee_history(12 * GWEI))).await;
+        let filler = TransactionFiller::default();
+        let calls = || {
+            std::mem::take(&mut *requests.lock().unwrap())
+                .into_iter()
+                .map(|(method, _)| method)
+                .collect::<Vec<_>>()
+        };
+
+        let transfer = serde_json::from_value::<MessageCall>(json!({
+            "from": "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f",
+            "to": "0x3535353535353535353535353535353535353535",
+            "value": "0xde0b6b3a7640000",
+        }))
+        .unwrap();
+        assert!(matches!(transfer, MessageCall::Legacy { tag: None, .. }));
+
+        // Untyped calls become EIP-1559 transactions once the chain has a base fee.
+        assert_eq!(
+            filler.fill(&client, transfer.clone()).await.unwrap(),
+            TransactionMessage::EIP1559 {
+                chain_id: 1.into(),
+                nonce: 7.into(),
+                to: Some(Address::repeat_byte(0x35)),
+                gas: 25200.into(),
+                max_fee_per_gas: U256::new(26 * GWEI),
+                max_priority_fee_per_gas: U256::new(2 * GWEI),
+                value: U256::new(1_000_000_000_000_000_000),
+                input: Bytes::default(),
+                access_list: vec![],
+            }
+        );
+        assert_eq!(
+            calls(),
+            [
+                "eth_chainId",
+                "eth_getTransactionCount",
+                "eth_feeHistory",
+                "eth_estimateGas",
+                "eth_getBlockByNumber",
+            ]
+        );
+
+        // Gas is capped at the block gas limit, invalid multipliers are rejected up front.
+        let generous = TransactionFiller {
+            gas_multiplier: 2000.0,
+            ..filler.clone()
+        };
+        assert!(matches!(
+            generous.fill(&client, transfer.clone()).await.unwrap(),
+            TransactionMessage::EIP1559 { gas, .. } if gas == 30_000_000.into()
+        ));
+        calls();
+        for gas_multiplier in [f64::NAN, f64::INFINITY, 0.5] {
+            let invalid = TransactionFiller {
+                gas_multiplier,
+                ..filler.clone()
+            };
+            assert!(invalid.fill(&client, transfer.clone()).await.is_err());
+            assert!(calls().is_empty());
+        }
+
+        // Explicitly legacy calls keep their type and skip fee history, fields set by the caller
+        // are not queried.
+        let legacy = serde_json::from_value::<MessageCall>(json!({
+            "type": "0x00",
+            "from": "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f",
+            "to": "0x3535353535353535353535353535353535353535",
+            "gas": "0x5208",
+        }))
+        .unwrap();
+        let filler = TransactionFiller {
+            gas_multiplier: 1.0,
+            ..filler
+        };
+        assert!(matches!(
+            filler.fill(&client, legacy).await.unwrap(),
+            TransactionMessage::Legacy {
+                chain_id: Some(_),
+                gas_price,
+                ..
+            } if gas_price == 5 * GWEI
+        ));
+        assert_eq!(
+            calls(),
+            ["eth_chainId", "eth_getTransactionCount", "eth_gasPrice"]
+        );
+
+        // The max fee leaves room above a high priority fee set by the caller.
+        let urgent = serde_json::from_value::<MessageCall>(json!({
+            "from": "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f",
+            "to": "0x3535353535353535353535353535353535353535",
+            "gas": "0x5208",
+            "maxPriorityFeePerGas": "0xba43b7400",
+        }))
+        .unwrap();
+        let TransactionMessage::EIP1559 {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            ..
+        } = filler.fill(&client, urgent).await.unwrap()
+        else {
+            panic!("expected EIP-1559 transaction");
+        };
+        assert_eq!(max_priority_fee_per_gas, 50 * GWEI);
+        assert_eq!(max_fee_per_gas, (2 * 12 + 50) * GWEI);
+        calls();
+
+        // Blob transactions also get a blob fee with room for the blob base fee to double.
+        let blob = serde_json::from_value::<MessageCall>(json!({
+            "from": "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f",
+            "to": "0x3535353535353535353535353535353535353535",
+            "maxPriorityFeePerGas": "0x1",
+            "blobVersionedHashes": [
+                "0x01e5276d91ac1ddb3b1c2d61295211220036e9a04be24c00f76916cc2659d004",
+            ],
+        }))
+        .unwrap();
+        let TransactionMessage::EIP4844 {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            max_fee_per_blob_gas,
+            ..
+        } = filler.fill(&client, blob).await.unwrap()
+        else {
+            panic!("expected EIP-4844 transaction");
+        };
+        assert_eq!(max_fee_per_gas, 24 * GWEI + 1);
+        assert_eq!(max_priority_fee_per_gas, 1);
+        assert_eq!(max_fee_per_blob_gas, 6);
+        calls();
+
+        // Before London, untyped calls are priced at `eth_gasPrice`.
+        handle.stop().unwrap();
+        let (client, handle, _) = mock_node(&node(|_| fee_history(0))).await;
+        assert!(matches!(
+            filler.fill(&client, transfer.clone()).await.unwrap(),
+            TransactionMessage::Legacy { gas, gas_price, .. }
+                if gas == 21000.into() && gas_price == 5 * GWEI
+        ));
+        assert!(filler
+            .fill(&client, transfer.clone().with_access_list(vec![]))
+            .await
+            .is_ok_and(|message| matches!(message, TransactionMessage::EIP2930 { .. })));
+
+        let mut anonymous = transfer;
+        if let MessageCall::Legacy { from, .. } = &mut anonymous {
+            *from = None;
+        }
+        assert!(filler.fill(&client, anonymous).await.is_err());
+
+        handle.stop().unwrap();
+    }
 }
@@ -3,6 +3,7 @@
 mod debug;
 mod engine;
 mod erigon;
+mod error;
 mod eth;
 mod net;
 mod otterscan;
@@ -14,6 +15,7 @@ mod web3;
 pub use debug::*;
 pub use engine::*;
 pub use erigon::*;
+pub use error::*;
 pub use eth::*;
 pub use net::*;
 pub use otterscan::*;
@@ -34,6 +36,14 @@ mod prelude {
     #[cfg(any(feature = "client", feature = "server"))]
     pub use jsonrpsee::core::RpcResult;
     pub use jsonrpsee::proc_macros::rpc;
+    #[cfg(feature = "client")]
+    pub use jsonrpsee::{
+        core::{
+            client::{ClientT, Subscription, SubscriptionClientT},
+            params::ArrayParams,
+        },
+        rpc_params,
+    };
     pub use serde::{
         de::{self, value::MapAccessDeserializer, Visitor},
         Deserialize, Deserializer, Serialize, Serializer,
@@ -150,6 +150,7 @@ pub trait OtterscanApi {
     async fn has_code(&self, address: Address, block_id: BlockId) -> RpcResult<bool>;
     #[method(name = "traceTransaction")]
     async fn trace_transaction(&self, hash: H256) -> RpcResult<Vec<TraceEntry>>;
+    /// Revert data of a failed transaction, see [`RevertReason::decode`].
     #[method(name = "getTransactionError")]
     async fn get_transaction_error(&self, hash: H256) -> RpcResult<Bytes>;
     #[method(name = "getTransactionBySenderAndNonce")]
@@ -2,7 +2,7 @@ use crate::prelude::*;
 use std::str::FromStr;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-/// A 64-bit unsigned integer (or tag - "latest", "earliest", "pending").
+/// A 64-bit unsigned integer (or tag - "latest", "earliest", "pending", "safe", "finalized").
 pub enum BlockNumber {
     /// Latest block.
     Latest,
@@ -10,6 +10,10 @@ pub enum BlockNumber {
     Earliest,
     /// Pending block (not yet part of the cannonical chain).
     Pending,
+    /// Latest block considered safe by the consensus layer.
+    Safe,
+    /// Latest block finalized by the consensus layer.
+    Finalized,
     /// A block number.
     Number(U64),
 }
@@ -24,6 +28,8 @@ impl Serialize for BlockNumber {
             BlockNumber::Latest => serializer.serialize_str("latest"),
             BlockNumber::Earliest => serializer.serialize_str("earliest"),
             BlockNumber::Pending => serializer.serialize_str("pending"),
+            BlockNumber::Safe => serializer.serialize_str("safe"),
+            BlockNumber::Finalized => serializer.serialize_str("finalized"),
         }
     }
 }
@@ -38,6 +44,8 @@ impl<'de> Deserialize<'de> for BlockNumber {
             "latest" => Self::Latest,
             "earliest" => Self::Earliest,
             "pending" => Self::Pending,
+            "safe" => Self::Safe,
+            "finalized" => Self::Finalized,
             n => BlockNumber::Number(U64::from_str(n).map_err(serde::de::Error::custom)?),
         })
     }
@@ -50,13 +58,59 @@ impl<T: Into<U64>> From<T> for BlockNumber {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
-/// BlockId is either a Block Number or a Hash.
+/// BlockId is either a Block Number or a Hash, bare or in EIP-1898 object form.
 #[serde(untagged)]
 pub enum BlockId {
     /// A 256-bit Hash.
     Hash(H256),
     /// A block number.
     Number(BlockNumber),
+    /// EIP-1898 `{"blockHash": ..., "requireCanonical": ...}` object.
+    #[serde(rename_all = "camelCase")]
+    HashObject {
+        block_hash: H256,
+        /// Fail if the block is not part of the canonical chain.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        require_canonical: Option<bool>,
+    },
+    /// EIP-1898 `{"blockNumber": ...}` object.
+    #[serde(rename_all = "camelCase")]
+    NumberObject { block_number: BlockNumber },
+}
+
+impl BlockId {
+    /// Block hash, if the block is identified by one.
+    pub fn hash(&self) -> Option<H256> {
+        match *self {
+            BlockId::Hash(hash)
+            | BlockId::HashObject {
+                block_hash: hash, ..
+            } => Some(hash),
+            BlockId::Number(_) | BlockId::NumberObject { .. } => None,
+        }
+    }
+
+    /// Block number or tag, if the block is identified by one.
+    pub fn number(&self) -> Option<BlockNumber> {
+        match *self {
+            BlockId::Number(number)
+            | BlockId::NumberObject {
+                block_number: number,
+            } => Some(number),
+            BlockId::Hash(_) | BlockId::HashObject { .. } => None,
+        }
+    }
+
+    /// Whether the block must be part of the canonical chain.
+    pub fn require_canonical(&self) -> bool {
+        matches!(
+            self,
+            BlockId::HashObject {
+                require_canonical: Some(true),
+                ..
+            }
+        )
+    }
 }
 
 impl From<BlockNumber> for BlockId {
@@ -71,6 +125,20 @@ impl From<H256> for BlockId {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Withdrawal from the beacon chain to the execution layer.
+pub struct Withdrawal {
+    /// Monotonically increasing withdrawal index.
+    pub index: U64,
+    /// Index of the withdrawing validator.
+    pub validator_index: U64,
+    /// Recipient of the withdrawn ether.
+    pub address: Address,
+    /// Withdrawn amount, in gwei.
+    pub amount: U64,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Block {
@@ -116,8 +184,27 @@ pub struct Block {
     pub transactions: Vec<Tx>,
     /// Block's uncles.
     pub uncles: ArrayVec<H256, 2>,
+    /// Base fee per gas, since London.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub base_fee_per_gas: Option<U256>,
+    /// Withdrawals trie root, since Shanghai.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawals_root: Option<H256>,
+    /// Total blob gas used by the block's transactions, since Cancun.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_gas_used: Option<U64>,
+    /// Blob gas in excess of the target, since Cancun.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub excess_blob_gas: Option<U64>,
+    /// Root of the parent beacon block, since Cancun.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_beacon_block_root: Option<H256>,
+    /// Hash of the execution layer requests, since Prague.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_hash: Option<H256>,
+    /// Validator withdrawals, since Shanghai.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawals: Option<Vec<Withdrawal>>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -138,13 +225,132 @@ pub struct Header {
     pub extra_data: Bytes,
     pub mix_hash: H256,
     pub nonce: H64,
+    /// Base fee per gas, since London.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub base_fee_per_gas: Option<U256>,
+    /// Withdrawals trie root, since Shanghai.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawals_root: Option<H256>,
+    /// Total blob gas used by the block's transactions, since Cancun.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_gas_used: Option<U64>,
+    /// Blob gas in excess of the target, since Cancun.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub excess_blob_gas: Option<U64>,
+    /// Root of the parent beacon block, since Cancun.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_beacon_block_root: Option<H256>,
+    /// Hash of the execution layer requests, since Prague.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_hash: Option<H256>,
+}
+
+impl Header {
+    /// Block hash, i.e. the keccak-256 hash of the RLP encoded header.
+    pub fn compute_hash(&self) -> H256 {
+        keccak256(rlp::encode(self))
+    }
+
+    /// Check that fork fields are set as some fork would: each one requires all the preceding
+    /// ones, and the Cancun fields come together.
+    fn check_fork_fields(&self) -> Result<(), HeaderError> {
+        let fields = [
+            ("baseFeePerGas", self.base_fee_per_gas.is_some()),
+            ("withdrawalsRoot", self.withdrawals_root.is_some()),
+            ("blobGasUsed", self.blob_gas_used.is_some()),
+            ("excessBlobGas", self.excess_blob_gas.is_some()),
+            (
+                "parentBeaconBlockRoot",
+                self.parent_beacon_block_root.is_some(),
+            ),
+            ("requestsHash", self.requests_hash.is_some()),
+        ];
+        let set = fields.iter().take_while(|(_, set)| *set).count();
+        if let Some((name, _)) = fields[set..].iter().find(|(_, set)| *set) {
+            return Err(HeaderError::UnexpectedField(name));
+        }
+        if (3..5).contains(&set) {
+            return Err(HeaderError::MissingField(fields[set].0));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HeaderError {
+    /// Field required to encode the header is missing, e.g. for a pending block.
+    MissingField(&'static str),
+    /// Fork field is set while a field of an earlier fork is not.
+    UnexpectedField(&'static str),
+}
+
+impl std::fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingField(name) => write!(f, "header field {name} is missing"),
+            Self::UnexpectedField(name) => {
+                write!(
+                    f,
+                    "header field {name} is set without those of earlier forks"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for HeaderError {}
+
+impl TryFrom<&Block> for Header {
+    type Error = HeaderError;
+
+    fn try_from(block: &Block) -> Result<Self, Self::Error> {
+        let missing = HeaderError::MissingField;
+        let (mix_hash, nonce) = match (block.mix_hash, block.nonce, block.seal_fields) {
+            (Some(mix_hash), Some(nonce), _) => (mix_hash, nonce),
+            (_, _, Some(seal_fields)) => seal_fields,
+            (None, _, None) => return Err(missing("mixHash")),
+            (_, None, None) => return Err(missing("nonce")),
+        };
+        let header = Self {
+            parent_hash: block.parent_hash,
+            sha3_uncles: block.sha3_uncles,
+            miner: block.miner,
+            state_root: block.state_root,
+            transactions_root: block.transactions_root,
+            receipts_root: block.receipts_root,
+            logs_bloom: block.logs_bloom.ok_or(missing("logsBloom"))?,
+            difficulty: block.difficulty,
+            number: block.number.ok_or(missing("number"))?,
+            gas_limit: block.gas_limit,
+            gas_used: block.gas_used,
+            timestamp: block.timestamp,
+            extra_data: block.extra_data.clone(),
+            mix_hash,
+            nonce,
+            base_fee_per_gas: block.base_fee_per_gas,
+            withdrawals_root: block.withdrawals_root,
+            blob_gas_used: block.blob_gas_used,
+            excess_blob_gas: block.excess_blob_gas,
+            parent_beacon_block_root: block.parent_beacon_block_root,
+            requests_hash: block.requests_hash,
+        };
+        header.check_fork_fields()?;
+        Ok(header)
+    }
+}
+
+impl TryFrom<Block> for Header {
+    type Error = HeaderError;
+
+    fn try_from(block: Block) -> Result<Self, Self::Error> {
+        Self::try_from(&block)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
     #[test]
     fn test_ser_de_block_number() {
         let block_number = BlockNumber::Earliest;
@@ -171,6 +377,22 @@ mod tests {
             block_number
         );
 
+        let block_number = BlockNumber::Safe;
+        let hexstring = r#""safe""#;
+        assert_eq!(serde_json::to_string(&block_number).unwrap(), hexstring);
+        assert_eq!(
+            serde_json::from_str::<BlockNumber>(hexstring).unwrap(),
+            block_number
+        );
+
+        let block_number = BlockNumber::Finalized;
+        let hexstring = r#""finalized""#;
+        assert_eq!(serde_json::to_string(&block_number).unwrap(), hexstring);
+        assert_eq!(
+            serde_json::from_str::<BlockNumber>(hexstring).unwrap(),
+            block_number
+        );
+
         let block_number = BlockNumber::Number(1.into());
         let hexstring = r#""0x1""#;
         assert_eq!(serde_json::to_string(&block_number).unwrap(), hexstring);
@@ -207,5 +429,182 @@ mod tests {
             serde_json::from_str::<BlockId>(block_hash).unwrap(),
             BlockId::Hash(H256::from([0; 32]))
         );
+
+        assert_eq!(
+            serde_json::from_str::<BlockId>(r#""finalized""#).unwrap(),
+            BlockId::Number(BlockNumber::Finalized)
+        );
+        assert_eq!(
+            serde_json::from_str::<BlockId>(r#""safe""#).unwrap(),
+            BlockId::Number(BlockNumber::Safe)
+        );
+    }
+
+    #[test]
+    fn test_ser_de_block_id_object() {
+        let block_id = BlockId::HashObject {
+            block_hash: H256::from([0; 32]),
+            require_canonical: Some(true),
+        };
+        let encoded = r#"{"blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","requireCanonical":true}"#;
+        assert_eq!(serde_json::to_string(&block_id).unwrap(), encoded);
+        assert_eq!(serde_json::from_str::<BlockId>(encoded).unwrap(), block_id);
+        assert!(block_id.require_canonical());
+        assert_eq!(block_id.hash(), Some(H256::from([0; 32])));
+
+        let block_id = BlockId::HashObject {
+            block_hash: H256::from([0; 32]),
+            require_canonical: None,
+        };
+        let encoded =
+            r#"{"blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000"}"#;
+        assert_eq!(serde_json::to_string(&block_id).unwrap(), encoded);
+        assert_eq!(serde_json::from_str::<BlockId>(encoded).unwrap(), block_id);
+        assert!(!block_id.require_canonical());
+
+        let block_id = BlockId::NumberObject {
+            block_number: 123.into(),
+        };
+        let encoded = r#"{"blockNumber":"0x7b"}"#;
+        assert_eq!(serde_json::to_string(&block_id).unwrap(), encoded);
+        assert_eq!(serde_json::from_str::<BlockId>(encoded).unwrap(), block_id);
+        assert_eq!(block_id.number(), Some(123.into()));
+        assert_eq!(block_id.hash(), None);
+
+        assert_eq!(
+            serde_json::from_str::<BlockId>(r#"{"blockNumber":"finalized"}"#).unwrap(),
+            BlockId::NumberObject {
+                block_number: BlockNumber::Finalized
+            }
+        );
+    }
+
+    #[test]
+    fn test_ser_de_block_prague() {
+        let encoded = json!({
+            "number": "0x1",
+            "hash": "0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3",
+            "parentHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "transactionsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "stateRoot": "0xd7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544",
+            "receiptsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "miner": "0x0000000000000000000000000000000000000000",
+            "difficulty": "0x0",
+            "totalDifficulty": "0xc70d815d562d3cfa955",
+            "sealFields": null,
+            "nonce": "0x0000000000000000",
+            "mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "extraData": "0x",
+            "size": "0x29a",
+            "gasLimit": "0x1c9c380",
+            "gasUsed": "0x0",
+            "timestamp": "0x64",
+            "transactions": [],
+            "uncles": [],
+            "baseFeePerGas": "0x7",
+            "withdrawalsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "withdrawals": [{
+                "index": "0x2d4f1e0",
+                "validatorIndex": "0x10ba2",
+                "address": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+                "amount": "0x11b6b4b",
+            }],
+            "blobGasUsed": "0x20000",
+            "excessBlobGas": "0x0",
+            "parentBeaconBlockRoot": "0xbb00000000000000000000000000000000000000000000000000000000000000",
+            "requestsHash": "0xe3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        });
+        let block = serde_json::from_value::<Block>(encoded.clone()).unwrap();
+        assert_eq!(
+            block.withdrawals,
+            Some(vec![Withdrawal {
+                index: 0x2d4f1e0.into(),
+                validator_index: 0x10ba2.into(),
+                address: Address::repeat_byte(0xAA),
+                amount: 0x11b6b4b.into(),
+            }])
+        );
+        assert_eq!(block.blob_gas_used, Some(0x20000.into()));
+        assert_eq!(serde_json::to_value(&block).unwrap(), encoded);
+    }
+
+    #[test]
+    fn test_ser_de_header_pre_london() {
+        let encoded = json!({
+            "parentHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+            "miner": "0x0000000000000000000000000000000000000000",
+            "stateRoot": "0xd7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544",
+            "transactionsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "receiptsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "difficulty": "0x400000000",
+            "number": "0x0",
+            "gasLimit": "0x1388",
+            "gasUsed": "0x0",
+            "timestamp": "0x0",
+            "extraData": "0x11bbe8db4e347b4e8c937c1c8370e4b5ed33adb3db69cbdb7a38e1e50b1b82fa",
+            "mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "nonce": "0x0000000000000042",
+        });
+        let header = serde_json::from_value::<Header>(encoded.clone()).unwrap();
+        assert_eq!(header.base_fee_per_gas, None);
+        assert_eq!(header.withdrawals_root, None);
+        assert_eq!(header.requests_hash, None);
+        assert_eq!(serde_json::to_value(&header).unwrap(), encoded);
+        // Mainnet genesis.
+        assert_eq!(
+            header.compute_hash(),
+            H256(hex_literal::hex!(
+                "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+            ))
+        );
+    }
+
+    /// Block with the given header and no transactions.
+    fn block_with_header(header: &serde_json::Value) -> Block {
+        let mut encoded = json!({
+            "totalDifficulty": null,
+            "sealFields": null,
+            "size": "0x0",
+            "transactions": [],
+            "uncles": [],
+        });
+        let object = encoded.as_object_mut().unwrap();
+        object.extend(header.as_object().unwrap().clone());
+        serde_json::from_value(encoded).unwrap()
+    }
+
+    #[test]
+    fn test_header_from_block() {
+        let headers: serde_json::Value =
+            serde_json::from_str(include_str!("../../testdata/headers.json")).unwrap();
+        for (name, header) in headers.as_object().unwrap() {
+            let block = block_with_header(header);
+            let header = Header::try_from(&block).unwrap();
+            assert_eq!(Some(header.compute_hash()), block.hash, "{name}");
+        }
+
+        let mut block = block_with_header(&headers["cancun"]);
+        block.parent_beacon_block_root = None;
+        assert_eq!(
+            Header::try_from(&block),
+            Err(HeaderError::MissingField("parentBeaconBlockRoot"))
+        );
+        block.withdrawals_root = None;
+        assert_eq!(
+            Header::try_from(&block),
+            Err(HeaderError::UnexpectedField("blobGasUsed"))
+        );
+
+        block.number = None;
+        block.nonce = None;
+        block.mix_hash = None;
+        assert_eq!(
+            Header::try_from(block),
+            Err(HeaderError::MissingField("mixHash"))
+        );
     }
 }
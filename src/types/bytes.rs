@@ -9,9 +9,9 @@ impl From<Bytes> for bytes::Bytes {
         v.0
     }
 }
-impl ToString for Bytes {
-    fn to_string(&self) -> String {
-        format!("0x{}", hex::encode(self.0.as_ref()))
+impl std::fmt::Display for Bytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "0x{}", hex::encode(self.0.as_ref()))
     }
 }
 impl AsRef<[u8]> for Bytes {
@@ -0,0 +1,903 @@
+use crate::prelude::*;
+use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
+
+/// Big-endian bytes of `v` without leading zeroes, as used by RLP for scalars.
+pub(crate) fn u256_to_be_trimmed(v: U256) -> Vec<u8> {
+    let bytes = v.to_be_bytes();
+    let leading = (v.leading_zeros() / 8) as usize;
+    bytes[leading..].to_vec()
+}
+
+fn append_u256(s: &mut RlpStream, v: U256) {
+    s.append(&u256_to_be_trimmed(v));
+}
+
+/// Signature scalars are encoded as integers, not as 32-byte strings.
+fn append_h256_scalar(s: &mut RlpStream, v: &H256) {
+    append_u256(s, U256::from_be_bytes(v.0));
+}
+
+fn append_to(s: &mut RlpStream, to: &Option<Address>) {
+    match to {
+        Some(to) => s.append(to),
+        None => s.append_empty_data(),
+    };
+}
+
+fn decode_scalar(rlp: &Rlp) -> Result<[u8; 32], DecoderError> {
+    let data = rlp.data()?;
+    if data.len() > 32 {
+        return Err(DecoderError::RlpIsTooBig);
+    }
+    if data.first() == Some(&0) {
+        return Err(DecoderError::RlpInvalidIndirection);
+    }
+    let mut out = [0; 32];
+    out[32 - data.len()..].copy_from_slice(data);
+    Ok(out)
+}
+
+fn decode_u256(rlp: &Rlp) -> Result<U256, DecoderError> {
+    decode_scalar(rlp).map(U256::from_be_bytes)
+}
+
+fn decode_h256_scalar(rlp: &Rlp) -> Result<H256, DecoderError> {
+    decode_scalar(rlp).map(H256)
+}
+
+fn decode_to(rlp: &Rlp) -> Result<Option<Address>, DecoderError> {
+    if rlp.is_empty() {
+        Ok(None)
+    } else {
+        rlp.as_val().map(Some)
+    }
+}
+
+fn decode_bytes(rlp: &Rlp) -> Result<Bytes, DecoderError> {
+    Ok(rlp.data()?.to_vec().into())
+}
+
+/// Check that `rlp` is a list of `count` items spanning the whole input.
+fn expect_list(rlp: &Rlp, len: usize, count: usize) -> Result<(), DecoderError> {
+    let info = rlp.payload_info()?;
+    if info.header_len + info.value_len != len {
+        return Err(DecoderError::RlpInconsistentLengthAndData);
+    }
+    if !rlp.is_list() {
+        return Err(DecoderError::RlpExpectedToBeList);
+    }
+    match rlp.item_count()? {
+        n if n < count => Err(DecoderError::RlpIsTooShort),
+        n if n > count => Err(DecoderError::RlpIncorrectListLen),
+        _ => Ok(()),
+    }
+}
+
+impl Encodable for AccessListEntry {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(2);
+        s.append(&self.address);
+        s.append_list(&self.storage_keys);
+    }
+}
+
+impl Decodable for AccessListEntry {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        if rlp.item_count()? != 2 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+        Ok(Self {
+            address: rlp.val_at(0)?,
+            storage_keys: rlp.list_at(1)?,
+        })
+    }
+}
+
+impl Encodable for SignedAuthorization {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(6);
+        append_u256(s, self.chain_id);
+        s.append(&self.address);
+        s.append(&self.nonce);
+        s.append(&self.y_parity);
+        append_u256(s, self.r);
+        append_u256(s, self.s);
+    }
+}
+
+impl Decodable for SignedAuthorization {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        if rlp.item_count()? != 6 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+        Ok(Self {
+            chain_id: decode_u256(&rlp.at(0)?)?,
+            address: rlp.val_at(1)?,
+            nonce: rlp.val_at(2)?,
+            y_parity: rlp.val_at(3)?,
+            r: decode_u256(&rlp.at(4)?)?,
+            s: decode_u256(&rlp.at(5)?)?,
+        })
+    }
+}
+
+/// Signature of a transaction as it appears in its RLP encoding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransactionSignature {
+    /// `v` of legacy transactions, including the EIP-155 chain id, y-parity of typed ones.
+    pub v: U64,
+    pub r: H256,
+    pub s: H256,
+}
+
+impl TransactionSignature {
+    /// Chain id encoded in `v` of a legacy transaction as per EIP-155, `None` for `v` of 27 or 28.
+    pub fn legacy_chain_id(&self) -> Option<U64> {
+        let v = self.v.as_u64();
+        (v >= 35).then(|| ((v - 35) / 2).into())
+    }
+
+    /// Parity of the y coordinate of `r`, from `v` normalised as per EIP-155 if `legacy`.
+    #[cfg(feature = "recovery")]
+    fn odd_y_parity(&self, legacy: bool) -> Result<bool, RecoveryError> {
+        match self.v.as_u64() {
+            v @ (27 | 28) if legacy => Ok(v == 28),
+            v @ 35.. if legacy => Ok((v - 35) % 2 == 1),
+            v @ (0 | 1) if !legacy => Ok(v == 1),
+            v => Err(RecoveryError::InvalidParity(v)),
+        }
+    }
+}
+
+impl TransactionMessage {
+    /// EIP-2718 transaction type.
+    pub fn transaction_type(&self) -> u8 {
+        match self {
+            Self::Legacy { .. } => 0,
+            Self::EIP2930 { .. } => 1,
+            Self::EIP1559 { .. } => 2,
+            Self::EIP4844 { .. } => 3,
+            Self::EIP7702 { .. } => 4,
+        }
+    }
+
+    fn field_count(&self) -> usize {
+        match self {
+            Self::Legacy { .. } => 6,
+            Self::EIP2930 { .. } => 8,
+            Self::EIP1559 { .. } => 9,
+            Self::EIP4844 { .. } => 11,
+            Self::EIP7702 { .. } => 10,
+        }
+    }
+
+    fn append_fields(&self, s: &mut RlpStream) {
+        match self {
+            Self::Legacy {
+                nonce,
+                to,
+                gas,
+                gas_price,
+                value,
+                input,
+                ..
+            } => {
+                s.append(nonce);
+                append_u256(s, *gas_price);
+                s.append(gas);
+                append_to(s, to);
+                append_u256(s, *value);
+                s.append(&input.as_ref());
+            }
+            Self::EIP2930 {
+                chain_id,
+                nonce,
+                to,
+                gas,
+                gas_price,
+                value,
+                input,
+                access_list,
+            } => {
+                s.append(chain_id);
+                s.append(nonce);
+                append_u256(s, *gas_price);
+                s.append(gas);
+                append_to(s, to);
+                append_u256(s, *value);
+                s.append(&input.as_ref());
+                s.append_list(access_list);
+            }
+            Self::EIP1559 {
+                chain_id,
+                nonce,
+                to,
+                gas,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                value,
+                input,
+                access_list,
+            } => {
+                s.append(chain_id);
+                s.append(nonce);
+                append_u256(s, *max_priority_fee_per_gas);
+                append_u256(s, *max_fee_per_gas);
+                s.append(gas);
+                append_to(s, to);
+                append_u256(s, *value);
+                s.append(&input.as_ref());
+                s.append_list(access_list);
+            }
+            Self::EIP4844 {
+                chain_id,
+                nonce,
+                to,
+                gas,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                max_fee_per_blob_gas,
+                value,
+                input,
+                access_list,
+                blob_versioned_hashes,
+            } => {
+                s.append(chain_id);
+                s.append(nonce);
+                append_u256(s, *max_priority_fee_per_gas);
+                append_u256(s, *max_fee_per_gas);
+                s.append(gas);
+                s.append(to);
+                append_u256(s, *value);
+                s.append(&input.as_ref());
+                s.append_list(access_list);
+                append_u256(s, *max_fee_per_blob_gas);
+                s.append_list(blob_versioned_hashes);
+            }
+            Self::EIP7702 {
+                chain_id,
+                nonce,
+                to,
+                gas,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                value,
+                input,
+                access_list,
+                authorization_list,
+            } => {
+                s.append(chain_id);
+                s.append(nonce);
+                append_u256(s, *max_priority_fee_per_gas);
+                append_u256(s, *max_fee_per_gas);
+                s.append(gas);
+                s.append(to);
+                append_u256(s, *value);
+                s.append(&input.as_ref());
+                s.append_list(access_list);
+                s.append_list(authorization_list);
+            }
+        }
+    }
+
+    /// Prefix the RLP list with the transaction type, unless legacy.
+    fn envelope(&self, rlp: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Legacy { .. } => rlp.to_vec(),
+            _ => {
+                let mut out = Vec::with_capacity(rlp.len() + 1);
+                out.push(self.transaction_type());
+                out.extend_from_slice(rlp);
+                out
+            }
+        }
+    }
+
+    /// Payload signed by the sender: the EIP-2718 envelope without signature, or for legacy
+    /// transactions the RLP list extended with the chain id as per EIP-155 if it is set.
+    pub fn encode_for_signing(&self) -> Vec<u8> {
+        let chain_id = match self {
+            Self::Legacy { chain_id, .. } => *chain_id,
+            _ => None,
+        };
+        self.signing_payload(chain_id)
+    }
+
+    fn signing_payload(&self, chain_id: Option<U64>) -> Vec<u8> {
+        let mut s = RlpStream::new_list(self.field_count() + chain_id.map_or(0, |_| 3));
+        self.append_fields(&mut s);
+        if let Some(chain_id) = chain_id {
+            s.append(&chain_id);
+            s.append_empty_data();
+            s.append_empty_data();
+        }
+        self.envelope(&s.out())
+    }
+
+    /// Hash signed by the sender.
+    pub fn signature_hash(&self) -> H256 {
+        keccak256(self.encode_for_signing())
+    }
+
+    /// Recover the account that signed this message with `signature`.
+    ///
+    /// The chain id of legacy transactions is taken from `v` and must match the message's, if
+    /// it has one.
+    #[cfg(feature = "recovery")]
+    pub fn recover_signer(
+        &self,
+        signature: &TransactionSignature,
+    ) -> Result<Address, RecoveryError> {
+        let payload = match self {
+            Self::Legacy { chain_id, .. } => {
+                let signed_chain_id = signature.legacy_chain_id();
+                match (*chain_id, signed_chain_id) {
+                    (Some(expected), got) if got != Some(expected) => {
+                        return Err(RecoveryError::ChainIdMismatch { expected, got })
+                    }
+                    _ => self.signing_payload(signed_chain_id),
+                }
+            }
+            _ => self.encode_for_signing(),
+        };
+        let odd_y_parity = signature.odd_y_parity(self.transaction_type() == 0)?;
+        recover_address(keccak256(payload), signature.r, signature.s, odd_y_parity)
+    }
+
+    /// EIP-2718 encoding of the signed transaction, plain RLP for legacy transactions.
+    pub fn encode_signed(&self, signature: &TransactionSignature) -> Vec<u8> {
+        let mut s = RlpStream::new_list(self.field_count() + 3);
+        self.append_fields(&mut s);
+        s.append(&signature.v);
+        append_h256_scalar(&mut s, &signature.r);
+        append_h256_scalar(&mut s, &signature.s);
+        self.envelope(&s.out())
+    }
+
+    /// Decode an EIP-2718 encoded or legacy RLP signed transaction.
+    ///
+    /// The chain id of legacy transactions is derived from `v` as per EIP-155. EIP-4844
+    /// transactions are expected in their canonical form, without blobs.
+    pub fn decode_signed(data: &[u8]) -> Result<(Self, TransactionSignature), DecoderError> {
+        let (&first, payload) = data.split_first().ok_or(DecoderError::RlpIsTooShort)?;
+        let (transaction_type, payload) = match first {
+            0xc0.. => (0, data),
+            0x01..=0x04 => (first, payload),
+            // A 0x00 prefix would decode as legacy and not re-encode to the same bytes.
+            _ => return Err(DecoderError::Custom("invalid transaction envelope")),
+        };
+        let rlp = Rlp::new(payload);
+        let field_count = match transaction_type {
+            0 => 6,
+            1 => 8,
+            2 => 9,
+            3 => 11,
+            _ => 10,
+        };
+        expect_list(&rlp, payload.len(), field_count + 3)?;
+
+        let signature = TransactionSignature {
+            v: rlp.val_at(field_count)?,
+            r: decode_h256_scalar(&rlp.at(field_count + 1)?)?,
+            s: decode_h256_scalar(&rlp.at(field_count + 2)?)?,
+        };
+
+        let message = match transaction_type {
+            0 => Self::Legacy {
+                chain_id: signature.legacy_chain_id(),
+                nonce: rlp.val_at(0)?,
+                gas_price: decode_u256(&rlp.at(1)?)?,
+                gas: rlp.val_at(2)?,
+                to: decode_to(&rlp.at(3)?)?,
+                value: decode_u256(&rlp.at(4)?)?,
+                input: decode_bytes(&rlp.at(5)?)?,
+            },
+            1 => Self::EIP2930 {
+                chain_id: rlp.val_at(0)?,
+                nonce: rlp.val_at(1)?,
+                gas_price: decode_u256(&rlp.at(2)?)?,
+                gas: rlp.val_at(3)?,
+                to: decode_to(&rlp.at(4)?)?,
+                value: decode_u256(&rlp.at(5)?)?,
+                input: decode_bytes(&rlp.at(6)?)?,
+                access_list: rlp.list_at(7)?,
+            },
+            2 => Self::EIP1559 {
+                chain_id: rlp.val_at(0)?,
+                nonce: rlp.val_at(1)?,
+                max_priority_fee_per_gas: decode_u256(&rlp.at(2)?)?,
+                max_fee_per_gas: decode_u256(&rlp.at(3)?)?,
+                gas: rlp.val_at(4)?,
+                to: decode_to(&rlp.at(5)?)?,
+                value: decode_u256(&rlp.at(6)?)?,
+                input: decode_bytes(&rlp.at(7)?)?,
+                access_list: rlp.list_at(8)?,
+            },
+            3 => Self::EIP4844 {
+                chain_id: rlp.val_at(0)?,
+                nonce: rlp.val_at(1)?,
+                max_priority_fee_per_gas: decode_u256(&rlp.at(2)?)?,
+                max_fee_per_gas: decode_u256(&rlp.at(3)?)?,
+                gas: rlp.val_at(4)?,
+                to: rlp.val_at(5)?,
+                value: decode_u256(&rlp.at(6)?)?,
+                input: decode_bytes(&rlp.at(7)?)?,
+                access_list: rlp.list_at(8)?,
+                max_fee_per_blob_gas: decode_u256(&rlp.at(9)?)?,
+                blob_versioned_hashes: rlp.list_at(10)?,
+            },
+            _ => Self::EIP7702 {
+                chain_id: rlp.val_at(0)?,
+                nonce: rlp.val_at(1)?,
+                max_priority_fee_per_gas: decode_u256(&rlp.at(2)?)?,
+                max_fee_per_gas: decode_u256(&rlp.at(3)?)?,
+                gas: rlp.val_at(4)?,
+                to: rlp.val_at(5)?,
+                value: decode_u256(&rlp.at(6)?)?,
+                input: decode_bytes(&rlp.at(7)?)?,
+                access_list: rlp.list_at(8)?,
+                authorization_list: rlp.list_at(9)?,
+            },
+        };
+
+        Ok((message, signature))
+    }
+}
+
+impl Transaction {
+    /// Signature as encoded on the wire. Typed transactions take their parity from `yParity`
+    /// when the node reports it, and from `v` otherwise.
+    pub fn signature(&self) -> TransactionSignature {
+        let v = match self.y_parity {
+            Some(y_parity) if self.message.transaction_type() != 0 => y_parity,
+            _ => self.v,
+        };
+        TransactionSignature {
+            v,
+            r: self.r,
+            s: self.s,
+        }
+    }
+
+    /// EIP-2718 encoding of the transaction, as accepted by `eth_sendRawTransaction`.
+    pub fn encode(&self) -> Bytes {
+        self.message.encode_signed(&self.signature()).into()
+    }
+
+    /// Recover the sender from the signature, to be checked against `from`.
+    #[cfg(feature = "recovery")]
+    pub fn recover_from(&self) -> Result<Address, RecoveryError> {
+        self.message.recover_signer(&self.signature())
+    }
+
+    /// Transaction hash, i.e. the keccak-256 hash of its EIP-2718 encoding.
+    pub fn compute_hash(&self) -> H256 {
+        keccak256(self.encode())
+    }
+}
+
+impl Encodable for Header {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        // Fork fields are appended up to the first one that is missing.
+        let optional = [
+            self.base_fee_per_gas
+                .map(|v| rlp::encode(&u256_to_be_trimmed(v))),
+            self.withdrawals_root.as_ref().map(rlp::encode),
+            self.blob_gas_used.as_ref().map(rlp::encode),
+            self.excess_blob_gas.as_ref().map(rlp::encode),
+            self.parent_beacon_block_root.as_ref().map(rlp::encode),
+            self.requests_hash.as_ref().map(rlp::encode),
+        ];
+        let optional = optional.into_iter().map_while(|v| v).collect::<Vec<_>>();
+
+        s.begin_list(15 + optional.len());
+        s.append(&self.parent_hash);
+        s.append(&self.sha3_uncles);
+        s.append(&self.miner);
+        s.append(&self.state_root);
+        s.append(&self.transactions_root);
+        s.append(&self.receipts_root);
+        s.append(&self.logs_bloom);
+        append_u256(s, self.difficulty);
+        s.append(&self.number);
+        s.append(&self.gas_limit);
+        s.append(&self.gas_used);
+        s.append(&self.timestamp);
+        s.append(&self.extra_data.as_ref());
+        s.append(&self.mix_hash);
+        s.append(&self.nonce);
+        for field in optional {
+            s.append_raw(&field, 1);
+        }
+    }
+}
+
+impl Decodable for Header {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        let count = rlp.item_count()?;
+        if !(15..=21).contains(&count) {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+        let optional = |i: usize| (i < count).then(|| rlp.at(i)).transpose();
+
+        Ok(Self {
+            parent_hash: rlp.val_at(0)?,
+            sha3_uncles: rlp.val_at(1)?,
+            miner: rlp.val_at(2)?,
+            state_root: rlp.val_at(3)?,
+            transactions_root: rlp.val_at(4)?,
+            receipts_root: rlp.val_at(5)?,
+            logs_bloom: rlp.val_at(6)?,
+            difficulty: decode_u256(&rlp.at(7)?)?,
+            number: rlp.val_at(8)?,
+            gas_limit: rlp.val_at(9)?,
+            gas_used: rlp.val_at(10)?,
+            timestamp: rlp.val_at(11)?,
+            extra_data: decode_bytes(&rlp.at(12)?)?,
+            mix_hash: rlp.val_at(13)?,
+            nonce: rlp.val_at(14)?,
+            base_fee_per_gas: optional(15)?.map(|v| decode_u256(&v)).transpose()?,
+            withdrawals_root: optional(16)?.map(|v| v.as_val()).transpose()?,
+            blob_gas_used: optional(17)?.map(|v| v.as_val()).transpose()?,
+            excess_blob_gas: optional(18)?.map(|v| v.as_val()).transpose()?,
+            parent_beacon_block_root: optional(19)?.map(|v| v.as_val()).transpose()?,
+            requests_hash: optional(20)?.map(|v| v.as_val()).transpose()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use serde_json::Value;
+
+    // Mainnet transaction 0x280cde7cdefe4b188750e76c888f13bd05ce9a4d7767730feefe8a0e50ca6fc4.
+    const LEGACY_TX: [u8; 343] = hex!("f9015482078b8505d21dba0083022ef1947a250d5630b4cf539739df2c5dacb4c659f2488d880c46549a521b13d8b8e47ff36ab50000000000000000000000000000000000000000000066ab5a608bd00a23f2fe000000000000000000000000000000000000000000000000000000000000008000000000000000000000000048c04ed5691981c42154c6167398f95e8f38a7ff00000000000000000000000000000000000000000000000000000000632ceac70000000000000000000000000000000000000000000000000000000000000002000000000000000000000000c02aaa39b223fe8d0a0e5c4f27ead9083c756cc20000000000000000000000006c6ee5e31d828de241282b9606c8e98ea48526e225a0c9077369501641a92ef7399ff81c21639ed4fd8fc69cb793cfa1dbfab342e10aa0615facb2f1bcf3274a354cfe384a38d0cc008a11c2dd23a69111bc6930ba27a8");
+    // Mainnet transaction 0xce4dc6d7a7549a98ee3b071b67e970879ff51b5b95d1c340bacd80fa1e1aab31.
+    const EIP1559_TX: [u8; 114] = hex!("02f86f0102843b9aca0085029e7822d68298f094d9e1459a7a482635700cbc20bbaf52d495ab9c9680841b55ba3ac080a0c199674fcb29f353693dd779c017823b954b3c69dffa3cd6b2a6ff7888798039a028ca912de909e7e6cdef9cdcaf24c54dd8c1032946dfa1d85c206b32a9064fe8");
+    // Mainnet transaction 0xf98c9f1a2f30ee316ea1db18c132ccab6383b8e4933ccf6259ca9d1f27d4a364.
+    const EIP2930_TX: [u8; 306] = hex!("01f9012e01826c6f850737be7600830493ef940c3de458b51a11da7d4616f42f66c861e3859d3e80b8c4f5b22c2a000000000000000000000000e67b950f4b84c5b06ee36ded6727a17443fe749300000000000000000000000000000000000000000000005f344f4a335cc50000000000000000000000000000000000000000000005c2f00b834b7f0000000000000000000000000000000000000000000000000005aa64a95b4a40400000000000000000000000000000000000000000000000000000000000000000000000010000000000000000000000000c3de458b51a11da7d4616f42f66c861e3859d3ec080a0c4023f0b8f7daecd7e143ef7aaa9b67bd059e643a6f2ae509a0e8483a3966e28a065a20662274cb5f7fe60a2af7dbd466244154440e73243f00b6a69bd08eacda4");
+    // Signed with key 0x4646..46 by an independent implementation, hash
+    // 0xb221a51ad59b5c6181d0028c5ac6f0d8a12e474768c4687f5f032c3d2e92c593.
+    const EIP7702_TX: [u8; 205] = hex!("04f8ca0106843b9aca008504a817c800830186a0949d8a62f656a8d1615c1294fd71e9cfb3e4855a4f8080c0f85cf85a019463c0c19a282a1b52b07dd5a65b58948a07dae32b0780a0c78af71e7956d3503968218104b099526ab8fa09b90a5999bc6356cd29e5a638a037bc90148184caead891d25fd9d174e5f75175426d3a4e06bc19dbaed4df63f701a046952e8bc4c66d5e79d01585cf4105daea75592d89132b2c0be7887543a6af98a009c579d47e12e248e9461b0497de18d921d78b30aaf43edf77252b14bbba192e");
+    // Pre-EIP-155 contract creation, signed with key 0x4646..46.
+    const PRE_EIP155_TX: [u8; 112] = hex!("f86e80850ba43b7400830186a080809d6080604052348015600f57600080fd5b50603f80601d6000396000f3fe1ca0791f57724d465a6b4f95dd57e42f9f6f2cab9c8fc35150966b7263cc4c1d3e6fa06533b5aed4e260e410e830025caa5464eea2dd4c25b3168e426d91ef00f6b53d");
+
+    fn round_trip(raw: &[u8]) -> (TransactionMessage, TransactionSignature) {
+        let (message, signature) = TransactionMessage::decode_signed(raw).unwrap();
+        assert_eq!(message.encode_signed(&signature), raw);
+        (message, signature)
+    }
+
+    #[test]
+    fn legacy_transaction() {
+        let raw = LEGACY_TX;
+        assert_eq!(
+            keccak256(raw),
+            H256(hex!(
+                "280cde7cdefe4b188750e76c888f13bd05ce9a4d7767730feefe8a0e50ca6fc4"
+            ))
+        );
+
+        let (message, signature) = round_trip(&raw);
+        let TransactionMessage::Legacy {
+            chain_id,
+            nonce,
+            to,
+            gas,
+            gas_price,
+            ..
+        } = &message
+        else {
+            panic!("expected legacy transaction, got {message:?}");
+        };
+        assert_eq!(*chain_id, Some(1.into()));
+        assert_eq!(*nonce, 0x78b.into());
+        assert_eq!(
+            *to,
+            Some(hex!("7a250d5630b4cf539739df2c5dacb4c659f2488d").into())
+        );
+        assert_eq!(*gas, 0x22ef1.into());
+        assert_eq!(*gas_price, U256::new(0x5d21dba00));
+        assert_eq!(signature.v, 0x25.into());
+    }
+
+    #[test]
+    fn eip1559_transaction() {
+        let raw = EIP1559_TX;
+        assert_eq!(
+            keccak256(raw),
+            H256(hex!(
+                "ce4dc6d7a7549a98ee3b071b67e970879ff51b5b95d1c340bacd80fa1e1aab31"
+            ))
+        );
+
+        let (message, signature) = round_trip(&raw);
+        assert_eq!(
+            message,
+            TransactionMessage::EIP1559 {
+                chain_id: 1.into(),
+                nonce: 2.into(),
+                to: Some(hex!("d9e1459a7a482635700cbc20bbaf52d495ab9c96").into()),
+                gas: 0x98f0.into(),
+                max_fee_per_gas: U256::new(0x029e7822d6),
+                max_priority_fee_per_gas: U256::new(0x3b9aca00),
+                value: 0.as_u256(),
+                input: hex!("1b55ba3a").to_vec().into(),
+                access_list: vec![],
+            }
+        );
+        assert_eq!(signature.v, 0.into());
+    }
+
+    #[test]
+    fn eip4844_transaction() {
+        // Mainnet transaction 0x93fc9daaa0726c3292a2e939df60f7e773c6a6a726a61ce43f4a217c64d85e87.
+        let message = TransactionMessage::EIP4844 {
+            chain_id: 1.into(),
+            nonce: 15435.into(),
+            to: hex!("a8cb082a5a689e0d594d7da1e2d72a3d63adc1bd").into(),
+            gas: 8000000.into(),
+            max_fee_per_gas: U256::new(10571233596),
+            max_priority_fee_per_gas: U256::new(1000000000),
+            max_fee_per_blob_gas: 1.as_u256(),
+            value: 0.as_u256(),
+            input: hex::decode(include_str!("../../testdata/blob_tx_input.hex").trim())
+                .unwrap()
+                .into(),
+            access_list: vec![],
+            blob_versioned_hashes: vec![
+                hex!("01e5276d91ac1ddb3b1c2d61295211220036e9a04be24c00f76916cc2659d004").into(),
+                hex!("0128eb58aff09fd3a7957cd80aa86186d5849569997cdfcfa23772811b706cc2").into(),
+            ],
+        };
+        let signature = TransactionSignature {
+            v: 0.into(),
+            r: hex!("6c173c3c8db3e3299f2f728d293b912c12e75243e3aa66911c2329b58434e2a4").into(),
+            s: hex!("7dd4d1c228cedc5a414a668ab165d9e888e61e4c3b44cd7daf9cdcc4cec5d6b2").into(),
+        };
+        let raw = message.encode_signed(&signature);
+        assert_eq!(raw[0], 3);
+        assert_eq!(
+            keccak256(&raw),
+            H256(hex!(
+                "93fc9daaa0726c3292a2e939df60f7e773c6a6a726a61ce43f4a217c64d85e87"
+            ))
+        );
+        assert_eq!(round_trip(&raw), (message, signature));
+    }
+
+    #[test]
+    fn eip2930_and_eip7702_transactions() {
+        let raw = EIP2930_TX;
+        assert_eq!(
+            keccak256(raw),
+            H256(hex!(
+                "f98c9f1a2f30ee316ea1db18c132ccab6383b8e4933ccf6259ca9d1f27d4a364"
+            ))
+        );
+        let (message, signature) = round_trip(&raw);
+        let TransactionMessage::EIP2930 {
+            chain_id,
+            nonce,
+            to,
+            gas,
+            gas_price,
+            access_list,
+            ..
+        } = &message
+        else {
+            panic!("expected EIP-2930 transaction, got {message:?}");
+        };
+        assert_eq!(*chain_id, 1.into());
+        assert_eq!(*nonce, 27759.into());
+        assert_eq!(
+            *to,
+            Some(hex!("0c3de458b51a11da7d4616f42f66c861e3859d3e").into())
+        );
+        assert_eq!(*gas, 300015.into());
+        assert_eq!(*gas_price, U256::new(31_000_000_000));
+        assert!(access_list.is_empty());
+        assert_eq!(signature.v, 0.into());
+
+        // Access list entries with storage keys.
+        let mut message = message;
+        let TransactionMessage::EIP2930 { access_list, .. } = &mut message else {
+            unreachable!()
+        };
+        access_list.push(AccessListEntry {
+            address: Address::repeat_byte(0xAA),
+            storage_keys: vec![H256::zero(), H256::repeat_byte(1)],
+        });
+        let raw = message.encode_signed(&signature);
+        assert_eq!(round_trip(&raw), (message, signature));
+
+        let raw = EIP7702_TX;
+        assert_eq!(
+            keccak256(raw),
+            H256(hex!(
+                "b221a51ad59b5c6181d0028c5ac6f0d8a12e474768c4687f5f032c3d2e92c593"
+            ))
+        );
+        let (message, _) = round_trip(&raw);
+        let TransactionMessage::EIP7702 {
+            authorization_list, ..
+        } = &message
+        else {
+            panic!("expected EIP-7702 transaction, got {message:?}");
+        };
+        assert_eq!(
+            authorization_list[0].address,
+            hex!("63c0c19a282a1b52b07dd5a65b58948a07dae32b").into()
+        );
+        assert_eq!(authorization_list[0].nonce, 7.into());
+    }
+
+    #[test]
+    fn compute_transaction_hash() {
+        // Mainnet transaction 0xce4dc6d7a7549a98ee3b071b67e970879ff51b5b95d1c340bacd80fa1e1aab31,
+        // as returned by `eth_getTransactionByHash`.
+        let tx = serde_json::from_value::<Transaction>(serde_json::json!({
+            "type": "0x2",
+            "chainId": "0x1",
+            "nonce": "0x2",
+            "to": "0xd9e1459a7a482635700cbc20bbaf52d495ab9c96",
+            "gas": "0x98f0",
+            "gasPrice": "0x29e7822d6",
+            "maxFeePerGas": "0x29e7822d6",
+            "maxPriorityFeePerGas": "0x3b9aca00",
+            "value": "0x0",
+            "input": "0x1b55ba3a",
+            "accessList": [],
+            "v": "0x0",
+            "yParity": "0x0",
+            "r": "0xc199674fcb29f353693dd779c017823b954b3c69dffa3cd6b2a6ff7888798039",
+            "s": "0x28ca912de909e7e6cdef9cdcaf24c54dd8c1032946dfa1d85c206b32a9064fe8",
+            "from": "0x001e2b7de757ba469a57bf6b23d982458a07efce",
+            "hash": "0xce4dc6d7a7549a98ee3b071b67e970879ff51b5b95d1c340bacd80fa1e1aab31",
+            "transactionIndex": null,
+            "blockHash": null,
+            "blockNumber": null,
+        }))
+        .unwrap();
+        assert_eq!(tx.compute_hash(), tx.hash);
+        assert_eq!(keccak256(tx.encode()), tx.hash);
+        #[cfg(feature = "recovery")]
+        assert_eq!(tx.recover_from().unwrap(), tx.from);
+
+        // Hashing and recovery agree on the parity when `v` is reported in legacy form.
+        let mut legacy_v = tx.clone();
+        legacy_v.v = 27.into();
+        assert_eq!(legacy_v.signature(), tx.signature());
+        assert_eq!(legacy_v.compute_hash(), tx.hash);
+        #[cfg(feature = "recovery")]
+        assert_eq!(legacy_v.recover_from().unwrap(), tx.from);
+
+        let mut tampered = tx;
+        tampered.r = H256::repeat_byte(1);
+        assert_ne!(tampered.compute_hash(), tampered.hash);
+    }
+
+    #[cfg(feature = "recovery")]
+    #[test]
+    fn recover_transaction_signers() {
+        let signer = |raw: &[u8]| {
+            let (message, signature) = TransactionMessage::decode_signed(raw).unwrap();
+            message.recover_signer(&signature)
+        };
+        let key_address = Address::from(hex!("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"));
+        assert_eq!(
+            signer(&LEGACY_TX),
+            Ok(hex!("a12e1462d0ced572f396f58b6e2d03894cd7c8a4").into())
+        );
+        assert_eq!(
+            signer(&EIP1559_TX),
+            Ok(hex!("001e2b7de757ba469a57bf6b23d982458a07efce").into())
+        );
+        assert_eq!(
+            signer(&EIP2930_TX),
+            Ok(hex!("f4b4e6fcd0cfb6e3d70821c136b9c5aa0e0936f2").into())
+        );
+        assert_eq!(signer(&EIP7702_TX), Ok(key_address));
+        assert_eq!(signer(&PRE_EIP155_TX), Ok(key_address));
+
+        let (message, signature) = TransactionMessage::decode_signed(&PRE_EIP155_TX).unwrap();
+        assert_eq!(signature.v, 28.into());
+        assert!(matches!(
+            message,
+            TransactionMessage::Legacy {
+                chain_id: None,
+                to: None,
+                ..
+            }
+        ));
+
+        // Legacy message without chain id, as some nodes report it, signed as per EIP-155.
+        let (mut message, signature) = TransactionMessage::decode_signed(&LEGACY_TX).unwrap();
+        let TransactionMessage::Legacy { chain_id, .. } = &mut message else {
+            unreachable!()
+        };
+        *chain_id = None;
+        assert_eq!(
+            message.recover_signer(&signature),
+            Ok(hex!("a12e1462d0ced572f396f58b6e2d03894cd7c8a4").into())
+        );
+        let TransactionMessage::Legacy { chain_id, .. } = &mut message else {
+            unreachable!()
+        };
+        *chain_id = Some(5.into());
+        assert_eq!(
+            message.recover_signer(&signature),
+            Err(RecoveryError::ChainIdMismatch {
+                expected: 5.into(),
+                got: Some(1.into()),
+            })
+        );
+
+        let (message, mut signature) = TransactionMessage::decode_signed(&EIP1559_TX).unwrap();
+        signature.v = 27.into();
+        assert_eq!(
+            message.recover_signer(&signature),
+            Err(RecoveryError::InvalidParity(27))
+        );
+        signature.v = 1.into();
+        assert_ne!(
+            message.recover_signer(&signature),
+            Ok(hex!("001e2b7de757ba469a57bf6b23d982458a07efce").into())
+        );
+    }
+
+    #[test]
+    fn reject_malformed_transactions() {
+        let raw = EIP1559_TX;
+        assert!(TransactionMessage::decode_signed(&[]).is_err());
+        assert!(TransactionMessage::decode_signed(&raw[..raw.len() - 1]).is_err());
+        assert!(TransactionMessage::decode_signed(&[&raw[..], &[0]].concat()).is_err());
+
+        let invalid_envelope = Err(DecoderError::Custom("invalid transaction envelope"));
+        for transaction_type in [0x00, 0x05, 0x7f, 0x80, 0xbf] {
+            let mut unknown = raw.to_vec();
+            unknown[0] = transaction_type;
+            assert_eq!(
+                TransactionMessage::decode_signed(&unknown),
+                invalid_envelope,
+                "{transaction_type:#x}"
+            );
+        }
+        let legacy = [&[0x00], &LEGACY_TX[..]].concat();
+        assert_eq!(TransactionMessage::decode_signed(&legacy), invalid_envelope);
+        round_trip(&LEGACY_TX);
+
+        let mut legacy_fields = raw.to_vec();
+        legacy_fields[0] = 0x01;
+        assert!(TransactionMessage::decode_signed(&legacy_fields).is_err());
+    }
+
+    #[test]
+    fn header() {
+        // Mainnet blocks 11117104 (Istanbul), 14764012 (London, an ommer of 14764013), 17585076
+        // (Shanghai) and 19449567 (Cancun), and Hoodi block 411443 (Prague).
+        let headers: Value =
+            serde_json::from_str(include_str!("../../testdata/headers.json")).unwrap();
+        let headers = headers.as_object().unwrap();
+        assert_eq!(
+            headers.keys().collect::<Vec<_>>(),
+            ["cancun", "istanbul", "london", "prague", "shanghai"]
+        );
+        for (name, header) in headers {
+            let hash: H256 = serde_json::from_value(header["hash"].clone()).unwrap();
+            let header: Header = serde_json::from_value(header.clone()).unwrap();
+            assert_eq!(header.compute_hash(), hash, "{name}");
+            let encoded = rlp::encode(&header);
+            assert_eq!(rlp::decode::<Header>(&encoded).unwrap(), header, "{name}");
+        }
+
+        let header = |name: &str| serde_json::from_value::<Header>(headers[name].clone()).unwrap();
+        let london = header("london");
+        assert!(london.base_fee_per_gas.is_some() && london.withdrawals_root.is_none());
+        let shanghai = header("shanghai");
+        assert!(shanghai.withdrawals_root.is_some() && shanghai.blob_gas_used.is_none());
+        assert!(header("prague").requests_hash.is_some());
+    }
+}
@@ -1,4 +1,6 @@
 use crate::prelude::*;
+use ethereum_types::BloomInput;
+use std::collections::BTreeMap;
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -14,10 +16,184 @@ pub struct TransactionLog {
     pub block_hash: Option<H256>,
     /// Block number, transaction is included in.
     pub block_number: Option<U64>,
+    /// Timestamp of the block, transaction is included in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_timestamp: Option<U64>,
     /// Log's address.
     pub address: Address,
     /// Log's data.
     pub data: Bytes,
     /// Log's Topics.
     pub topics: Vec<H256>,
+    /// Whether the log was retracted by a chain reorganization.
+    #[serde(default)]
+    pub removed: bool,
+}
+
+/// Compute the bloom filter of the given logs, as found in receipts and block headers.
+pub fn logs_bloom<'a>(logs: impl IntoIterator<Item = &'a TransactionLog>) -> Bloom {
+    let mut bloom = Bloom::zero();
+    for log in logs {
+        bloom.accrue(BloomInput::Raw(log.address.as_bytes()));
+        for topic in &log.topics {
+            bloom.accrue(BloomInput::Raw(topic.as_bytes()));
+        }
+    }
+    bloom
+}
+
+/// Logs that are currently canonical, maintained from a sequence of logs that may be retracted by
+/// chain reorganizations, e.g. from `eth_subscribe("logs")` or `eth_getFilterChanges`.
+///
+/// Logs are ordered by block number and log index. Pending logs, which have no block hash or log
+/// index, are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct CanonicalLogs {
+    logs: BTreeMap<(U64, U64, H256), TransactionLog>,
+}
+
+impl CanonicalLogs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the log, or remove it if it is marked as `removed`. Returns whether the view changed.
+    pub fn apply(&mut self, log: TransactionLog) -> bool {
+        let (Some(block_hash), Some(log_index)) = (log.block_hash, log.log_index) else {
+            return false;
+        };
+        let key = (log.block_number.unwrap_or_default(), log_index, block_hash);
+        if log.removed {
+            self.logs.remove(&key).is_some()
+        } else {
+            self.logs.insert(key, log.clone()) != Some(log)
+        }
+    }
+
+    /// Forget logs below `block_number`, e.g. once it is finalized.
+    pub fn prune(&mut self, block_number: U64) {
+        self.logs = self
+            .logs
+            .split_off(&(block_number, U64::zero(), H256::zero()));
+    }
+
+    /// Canonical logs in chain order.
+    pub fn iter(&self) -> impl Iterator<Item = &TransactionLog> {
+        self.logs.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.logs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.logs.is_empty()
+    }
+}
+
+impl Extend<TransactionLog> for CanonicalLogs {
+    fn extend<T: IntoIterator<Item = TransactionLog>>(&mut self, iter: T) {
+        for log in iter {
+            self.apply(log);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn log(block: u8, index: u64, removed: bool) -> TransactionLog {
+        TransactionLog {
+            log_index: Some(index.into()),
+            transaction_index: Some(0.into()),
+            transaction_hash: Some(H256::repeat_byte(block)),
+            block_hash: Some(H256::repeat_byte(block)),
+            block_number: Some(u64::from(block & 0x0f).into()),
+            block_timestamp: None,
+            address: Address::repeat_byte(0x11),
+            data: Bytes::default(),
+            topics: vec![],
+            removed,
+        }
+    }
+
+    #[test]
+    fn test_ser_de_log() {
+        let encoded = json!({
+            "address": "0x1111111111111111111111111111111111111111",
+            "blockHash": "0x0101010101010101010101010101010101010101010101010101010101010101",
+            "blockNumber": "0x1",
+            "blockTimestamp": "0x6553f100",
+            "data": "0x",
+            "logIndex": "0x0",
+            "removed": true,
+            "topics": [],
+            "transactionHash": "0x0101010101010101010101010101010101010101010101010101010101010101",
+            "transactionIndex": "0x0",
+        });
+        let mut expected = log(0x01, 0, true);
+        expected.block_timestamp = Some(0x6553f100.into());
+        assert_eq!(
+            serde_json::from_value::<TransactionLog>(encoded.clone()).unwrap(),
+            expected
+        );
+        assert_eq!(serde_json::to_value(&expected).unwrap(), encoded);
+
+        let mut encoded = encoded;
+        let object = encoded.as_object_mut().unwrap();
+        object.remove("removed");
+        object.remove("blockTimestamp");
+        assert_eq!(
+            serde_json::from_value::<TransactionLog>(encoded).unwrap(),
+            log(0x01, 0, false)
+        );
+    }
+
+    #[test]
+    fn test_logs_bloom() {
+        let receipts: serde_json::Value =
+            serde_json::from_str(include_str!("../../testdata/receipts.json")).unwrap();
+        for receipt in receipts.as_object().unwrap().values() {
+            let receipt: TransactionReceipt = serde_json::from_value(receipt.clone()).unwrap();
+            assert_eq!(logs_bloom(&receipt.logs), receipt.logs_bloom);
+        }
+        assert_eq!(logs_bloom(&[]), Bloom::zero());
+    }
+
+    #[test]
+    fn test_canonical_logs_reorg() {
+        let mut logs = CanonicalLogs::new();
+        // Blocks 1 and 2 on the original chain.
+        logs.extend([
+            log(0x01, 0, false),
+            log(0x02, 1, false),
+            log(0x02, 2, false),
+        ]);
+        assert_eq!(logs.len(), 3);
+        assert!(!logs.apply(log(0x02, 1, false)));
+
+        // Block 2 is replaced by block 0x12 at the same height.
+        assert!(logs.apply(log(0x02, 1, true)));
+        assert!(logs.apply(log(0x02, 2, true)));
+        assert!(!logs.apply(log(0x02, 2, true)));
+        assert!(logs.apply(log(0x12, 1, false)));
+        assert_eq!(
+            logs.iter()
+                .map(|l| l.block_hash.unwrap())
+                .collect::<Vec<_>>(),
+            vec![H256::repeat_byte(0x01), H256::repeat_byte(0x12)]
+        );
+
+        let mut pending = log(0x03, 0, false);
+        pending.block_hash = None;
+        assert!(!logs.apply(pending));
+
+        logs.prune(2.into());
+        assert_eq!(
+            logs.iter().cloned().collect::<Vec<_>>(),
+            vec![log(0x12, 1, false)]
+        );
+    }
 }
@@ -1,14 +1,39 @@
 mod block;
 mod bytes;
+mod encoding;
 mod log;
+mod proof;
 mod receipt;
+#[cfg(feature = "recovery")]
+mod recovery;
+mod revert;
+#[cfg(feature = "signer")]
+mod signer;
 mod trace;
 mod transaction;
 
-pub use self::{block::*, bytes::*, log::*, receipt::*, trace::*, transaction::*};
+#[cfg(feature = "recovery")]
+pub use self::recovery::*;
+#[cfg(feature = "signer")]
+pub use self::signer::*;
+pub use self::{
+    block::*, bytes::*, encoding::*, log::*, proof::*, receipt::*, revert::*, trace::*,
+    transaction::*,
+};
 
+use ethereum_types::H256;
 use serde::de::Error;
 use serde::{Deserialize, Serialize};
+use tiny_keccak::{Hasher, Keccak};
+
+/// Keccak-256 hash of `data`.
+pub fn keccak256(data: impl AsRef<[u8]>) -> H256 {
+    let mut hasher = Keccak::v256();
+    hasher.update(data.as_ref());
+    let mut out = H256::zero();
+    hasher.finalize(out.as_bytes_mut());
+    out
+}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct StringU64(pub u64);
@@ -0,0 +1,336 @@
+use super::encoding::u256_to_be_trimmed;
+use super::Header;
+use crate::prelude::*;
+use rlp::{Rlp, RlpStream};
+
+/// Root of an empty Merkle-Patricia trie.
+pub const EMPTY_ROOT: H256 = H256([
+    0x56, 0xe8, 0x1f, 0x17, 0x1b, 0xcc, 0x55, 0xa6, 0xff, 0x83, 0x45, 0xe6, 0x92, 0xc0, 0xf8, 0x6e,
+    0x5b, 0x48, 0xe0, 0x1b, 0x99, 0x6c, 0xad, 0xc0, 0x01, 0x62, 0x2f, 0xb5, 0xe3, 0x63, 0xb4, 0x21,
+]);
+
+/// Hash of empty EVM code.
+pub const EMPTY_CODE_HASH: H256 = H256([
+    0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7, 0x03, 0xc0,
+    0xe5, 0x00, 0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04, 0x5d, 0x85, 0xa4, 0x70,
+]);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Storage slot proof, as returned by `eth_getProof` (EIP-1186).
+pub struct StorageProof {
+    /// Storage slot.
+    pub key: U256,
+    /// Value of the slot.
+    pub value: U256,
+    /// Trie nodes from the storage root down to the slot.
+    pub proof: Vec<Bytes>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Account proof, as returned by `eth_getProof` (EIP-1186).
+pub struct AccountProof {
+    /// Account's address.
+    pub address: Address,
+    /// Trie nodes from the state root down to the account.
+    pub account_proof: Vec<Bytes>,
+    /// Account's balance.
+    pub balance: U256,
+    /// Hash of account's code.
+    pub code_hash: H256,
+    /// Account's nonce.
+    pub nonce: U64,
+    /// Root of account's storage trie.
+    pub storage_hash: H256,
+    /// Proofs for the requested storage slots.
+    pub storage_proof: Vec<StorageProof>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofError {
+    /// Proof ended before reaching the key.
+    MissingNode,
+    /// Node does not hash to the reference held by its parent.
+    HashMismatch { expected: H256, got: H256 },
+    /// Node is not valid RLP or not a valid trie node.
+    InvalidNode(String),
+    /// Proof resolves to a value other than the claimed one.
+    ValueMismatch,
+    /// Storage proof for the given slot failed.
+    Storage { key: U256, error: Box<ProofError> },
+}
+
+impl std::fmt::Display for ProofError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingNode => write!(f, "proof is missing a node"),
+            Self::HashMismatch { expected, got } => {
+                write!(f, "node hash mismatch: expected {expected:?}, got {got:?}")
+            }
+            Self::InvalidNode(e) => write!(f, "invalid trie node: {e}"),
+            Self::ValueMismatch => write!(f, "proven value does not match the claimed one"),
+            Self::Storage { key, error } => write!(f, "storage slot 0x{key:x}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ProofError {}
+
+impl From<rlp::DecoderError> for ProofError {
+    fn from(e: rlp::DecoderError) -> Self {
+        Self::InvalidNode(e.to_string())
+    }
+}
+
+enum NodeRef<'a> {
+    Hash(H256),
+    Inline(&'a [u8]),
+}
+
+fn child_ref<'a>(item: Rlp<'a>) -> Result<Option<NodeRef<'a>>, ProofError> {
+    if item.is_list() {
+        return Ok(Some(NodeRef::Inline(item.as_raw())));
+    }
+    match item.data()? {
+        [] => Ok(None),
+        hash if hash.len() == 32 => Ok(Some(NodeRef::Hash(H256::from_slice(hash)))),
+        other => Err(ProofError::InvalidNode(format!(
+            "child reference of {} bytes",
+            other.len()
+        ))),
+    }
+}
+
+/// Decode hex-prefix encoded path into nibbles and leaf flag.
+fn decode_path(encoded: &[u8]) -> Result<(bool, Vec<u8>), ProofError> {
+    let (first, rest) = encoded
+        .split_first()
+        .ok_or_else(|| ProofError::InvalidNode("empty path".into()))?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return Err(ProofError::InvalidNode(format!("path flag {flag}")));
+    }
+
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    }
+    for b in rest {
+        nibbles.push(b >> 4);
+        nibbles.push(b & 0x0f);
+    }
+    Ok((flag & 2 == 2, nibbles))
+}
+
+/// Walk the proof from `root` along `keccak(key)`, returning the stored value if it exists.
+pub fn verify_proof(
+    root: H256,
+    key: &[u8],
+    proof: &[Bytes],
+) -> Result<Option<Vec<u8>>, ProofError> {
+    if root == EMPTY_ROOT && proof.is_empty() {
+        return Ok(None);
+    }
+
+    let path = keccak256(key)
+        .as_bytes()
+        .iter()
+        .flat_map(|b| [b >> 4, b & 0x0f])
+        .collect::<Vec<_>>();
+    let mut path = &path[..];
+    let mut proof = proof.iter();
+    let mut next = NodeRef::Hash(root);
+
+    loop {
+        let node = match next {
+            NodeRef::Hash(expected) => {
+                let node = proof.next().ok_or(ProofError::MissingNode)?.as_ref();
+                let got = keccak256(node);
+                if got != expected {
+                    return Err(ProofError::HashMismatch { expected, got });
+                }
+                node
+            }
+            NodeRef::Inline(node) => node,
+        };
+
+        let node = Rlp::new(node);
+        match node.item_count()? {
+            17 => {
+                let Some((&nibble, rest)) = path.split_first() else {
+                    let value = node.at(16)?.data()?;
+                    return Ok((!value.is_empty()).then(|| value.to_vec()));
+                };
+                path = rest;
+                match child_ref(node.at(nibble as usize)?)? {
+                    Some(child) => next = child,
+                    None => return Ok(None),
+                }
+            }
+            2 => {
+                let (is_leaf, node_path) = decode_path(node.at(0)?.data()?)?;
+                if is_leaf {
+                    if node_path != path {
+                        return Ok(None);
+                    }
+                    return Ok(Some(node.at(1)?.data()?.to_vec()));
+                }
+                if !path.starts_with(&node_path) {
+                    return Ok(None);
+                }
+                path = &path[node_path.len()..];
+                match child_ref(node.at(1)?)? {
+                    Some(child) => next = child,
+                    None => return Err(ProofError::InvalidNode("empty extension child".into())),
+                }
+            }
+            n => return Err(ProofError::InvalidNode(format!("node with {n} items"))),
+        }
+    }
+}
+
+impl StorageProof {
+    /// Verify this proof against the storage root of the account.
+    pub fn verify(&self, storage_root: H256) -> Result<(), ProofError> {
+        let value = verify_proof(storage_root, &self.key.to_be_bytes(), &self.proof)?;
+        let expected = (self.value != 0).then(|| rlp::encode(&u256_to_be_trimmed(self.value)));
+        if value.as_deref() == expected.as_deref() {
+            Ok(())
+        } else {
+            Err(ProofError::ValueMismatch)
+        }
+    }
+}
+
+impl AccountProof {
+    fn is_empty_account(&self) -> bool {
+        self.nonce.is_zero()
+            && self.balance == 0
+            && self.code_hash == EMPTY_CODE_HASH
+            && self.storage_hash == EMPTY_ROOT
+    }
+
+    fn rlp_account(&self) -> Vec<u8> {
+        let mut s = RlpStream::new_list(4);
+        s.append(&self.nonce);
+        s.append(&u256_to_be_trimmed(self.balance));
+        s.append(&self.storage_hash);
+        s.append(&self.code_hash);
+        s.out().to_vec()
+    }
+
+    /// Verify the account against `state_root` of a block or header, and every storage proof
+    /// against the account's `storage_hash`.
+    ///
+    /// A non-existent account is proven by an exclusion proof with all fields set to their
+    /// empty values.
+    pub fn verify(&self, state_root: H256) -> Result<(), ProofError> {
+        let value = verify_proof(state_root, self.address.as_bytes(), &self.account_proof)?;
+        let matches = match value {
+            Some(value) => value == self.rlp_account(),
+            None => self.is_empty_account(),
+        };
+        if !matches {
+            return Err(ProofError::ValueMismatch);
+        }
+
+        for storage_proof in &self.storage_proof {
+            storage_proof
+                .verify(self.storage_hash)
+                .map_err(|error| ProofError::Storage {
+                    key: storage_proof.key,
+                    error: Box::new(error),
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Verify the proof against the state root of `header`, see [`Self::verify`].
+    pub fn verify_against(&self, header: &Header) -> Result<(), ProofError> {
+        self.verify(header.state_root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    /// State root of the mainnet block `testdata/get_proof.json` was taken at.
+    const STATE_ROOT: H256 = H256(hex!(
+        "57e6e864257daf9d96aaca31edd0cfe4e3892f09061e727c57ab56197dd59287"
+    ));
+
+    fn fixture() -> AccountProof {
+        serde_json::from_str(include_str!("../../testdata/get_proof.json")).unwrap()
+    }
+
+    #[test]
+    fn verify_account_proofs() {
+        let proof = fixture();
+        assert_eq!(
+            proof.address,
+            hex!("7ae1d57b58fa6411f32948314badd83583ee0e8c").into()
+        );
+        assert_eq!(keccak256(&proof.account_proof[0]), STATE_ROOT);
+        proof.verify(STATE_ROOT).unwrap();
+        assert_eq!(proof.storage_proof.len(), 1);
+        assert_eq!(proof.storage_proof[0].value, 0);
+        proof.storage_proof[0].verify(proof.storage_hash).unwrap();
+        assert_eq!(
+            keccak256(rlp::NULL_RLP),
+            EMPTY_ROOT,
+            "empty trie root is keccak of empty string"
+        );
+        assert_eq!(keccak256([]), EMPTY_CODE_HASH);
+    }
+
+    #[test]
+    fn verify_against_header() {
+        let headers: serde_json::Value =
+            serde_json::from_str(include_str!("../../testdata/headers.json")).unwrap();
+        let mut header: Header = serde_json::from_value(headers["cancun"].clone()).unwrap();
+        let proof = fixture();
+        assert!(proof.verify_against(&header).is_err());
+        header.state_root = STATE_ROOT;
+        proof.verify_against(&header).unwrap();
+    }
+
+    #[test]
+    fn reject_tampered_proofs() {
+        let proof = fixture();
+
+        let mut tampered = proof.clone();
+        tampered.balance += 1;
+        assert_eq!(tampered.verify(STATE_ROOT), Err(ProofError::ValueMismatch));
+
+        let mut tampered = proof.clone();
+        tampered.nonce = 2.into();
+        assert_eq!(tampered.verify(STATE_ROOT), Err(ProofError::ValueMismatch));
+
+        let mut tampered = proof.clone();
+        tampered.storage_proof[0].value = 1.as_u256();
+        assert!(matches!(
+            tampered.verify(STATE_ROOT),
+            Err(ProofError::Storage { .. })
+        ));
+
+        let mut tampered = proof.clone();
+        tampered.storage_proof[0].proof.pop();
+        assert!(matches!(
+            tampered.verify(STATE_ROOT),
+            Err(ProofError::Storage { .. })
+        ));
+
+        let mut tampered = proof.clone();
+        tampered.account_proof.pop();
+        assert_eq!(tampered.verify(STATE_ROOT), Err(ProofError::MissingNode));
+
+        assert!(matches!(
+            proof.verify(H256::repeat_byte(0xAA)),
+            Err(ProofError::HashMismatch { .. })
+        ));
+    }
+}
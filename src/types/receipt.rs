@@ -1,6 +1,60 @@
 use crate::types::log::TransactionLog;
 use ethereum_types::{Address, Bloom, H256, U64};
-use serde::{Deserialize, Serialize};
+use ethnum::U256;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Outcome of a transaction, as reported by its receipt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum TransactionOutcome {
+    /// Execution status since Byzantium (EIP-658), `true` on success.
+    #[serde(rename = "status", serialize_with = "status_code::serialize")]
+    Status(bool),
+    /// Intermediate state root before Byzantium.
+    #[serde(rename = "root")]
+    PostState(H256),
+}
+
+mod status_code {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(status: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+        U64::from(*status as u8).serialize(serializer)
+    }
+
+    pub fn from_code<E: serde::de::Error>(code: U64) -> Result<bool, E> {
+        match code.as_u64() {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(E::custom(format!("invalid receipt status {other}"))),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TransactionOutcome {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // Some nodes serve `"root": null` next to the status of post-Byzantium receipts.
+        #[derive(Deserialize)]
+        struct Outcome {
+            #[serde(default)]
+            status: Option<U64>,
+            #[serde(default)]
+            root: Option<H256>,
+        }
+
+        match Outcome::deserialize(deserializer)? {
+            Outcome {
+                status: Some(code), ..
+            } => status_code::from_code(code).map(TransactionOutcome::Status),
+            Outcome {
+                root: Some(root), ..
+            } => Ok(TransactionOutcome::PostState(root)),
+            _ => Err(serde::de::Error::missing_field("status")),
+        }
+    }
+}
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -28,6 +82,119 @@ pub struct TransactionReceipt {
     pub logs: Vec<TransactionLog>,
     /// Bloom logs.
     pub logs_bloom: Bloom,
-    /// Status, 1 for success, 0 for failure.
-    pub status: U64,
+    /// Transaction type, absent before Berlin.
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub transaction_type: Option<U64>,
+    /// Gas price actually paid per unit of gas.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effective_gas_price: Option<U256>,
+    /// Status or, before Byzantium, post-transaction state root.
+    #[serde(flatten)]
+    pub outcome: TransactionOutcome,
+    /// Blob gas used by an EIP-4844 transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_gas_used: Option<U64>,
+    /// Blob gas price paid by an EIP-4844 transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_gas_price: Option<U256>,
+}
+
+impl TransactionReceipt {
+    /// Whether the transaction succeeded, unknown before Byzantium.
+    pub fn status(&self) -> Option<bool> {
+        match self.outcome {
+            TransactionOutcome::Status(status) => Some(status),
+            TransactionOutcome::PostState(_) => None,
+        }
+    }
+
+    /// Total fee paid, including blob gas. `None` if the node did not report the effective gas
+    /// price, or reported prices the fee overflows with.
+    pub fn fee(&self) -> Option<U256> {
+        let execution = self
+            .effective_gas_price?
+            .checked_mul(U256::from(self.gas_used.as_u64()))?;
+        let blob = match (self.blob_gas_used, self.blob_gas_price) {
+            (Some(used), Some(price)) => U256::from(used.as_u64()).checked_mul(price)?,
+            _ => U256::ZERO,
+        };
+        execution.checked_add(blob)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use serde_json::{json, Value};
+
+    fn fixture(era: &str) -> Value {
+        let v: Value = serde_json::from_str(include_str!("../../testdata/receipts.json")).unwrap();
+        v[era].clone()
+    }
+
+    fn round_trip(encoded: Value) -> TransactionReceipt {
+        let receipt = serde_json::from_value::<TransactionReceipt>(encoded.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&receipt).unwrap(), encoded);
+        receipt
+    }
+
+    #[test]
+    fn test_ser_de_receipt_pre_byzantium() {
+        // Mainnet block 1000000.
+        let receipt = round_trip(fixture("frontier"));
+        assert_eq!(
+            receipt.outcome,
+            TransactionOutcome::PostState(H256(hex!(
+                "284d35bf53b82ef480ab4208527325477439c64fb90ef518450f05ee151c8e10"
+            )))
+        );
+        assert_eq!(receipt.status(), None);
+        assert_eq!(receipt.transaction_type, Some(0.into()));
+        assert_eq!(receipt.fee(), Some(U256::new(0x723c * 0x12bfb19e60)));
+
+        let encoded = serde_json::to_value(&receipt).unwrap();
+        assert!(encoded.get("status").is_none());
+    }
+
+    #[test]
+    fn test_ser_de_receipt_eip1559() {
+        // Mainnet block 19526841.
+        let receipt = round_trip(fixture("cancun"));
+        assert_eq!(receipt.outcome, TransactionOutcome::Status(true));
+        assert_eq!(receipt.transaction_type, Some(2.into()));
+        assert_eq!(receipt.effective_gas_price, Some(U256::new(0xfb0f6e8c9)));
+        assert_eq!(receipt.fee(), Some(U256::new(0xbde1 * 0xfb0f6e8c9)));
+
+        let mut overflowing = receipt.clone();
+        overflowing.effective_gas_price = Some(U256::MAX);
+        assert_eq!(overflowing.fee(), None);
+        overflowing.effective_gas_price = Some(U256::ONE);
+        overflowing.blob_gas_used = Some(1.into());
+        overflowing.blob_gas_price = Some(U256::MAX);
+        assert_eq!(overflowing.fee(), None);
+
+        let encoded = serde_json::to_value(&receipt).unwrap();
+        assert_eq!(encoded["status"], "0x1");
+        assert!(encoded.get("root").is_none());
+    }
+
+    #[test]
+    fn test_ser_de_receipt_legacy_fields() {
+        // Mainnet block 5417333, served before `type` and `effectiveGasPrice` were added.
+        let mut encoded = fixture("byzantium");
+        let receipt = serde_json::from_value::<TransactionReceipt>(encoded.clone()).unwrap();
+        assert_eq!(receipt.status(), Some(true));
+        assert_eq!(receipt.transaction_type, None);
+        assert_eq!(receipt.fee(), None);
+        assert_eq!(receipt.logs.len(), 2);
+
+        // `"root": null` is left off.
+        encoded.as_object_mut().unwrap().remove("root");
+        assert_eq!(serde_json::to_value(&receipt).unwrap(), encoded);
+
+        let mut invalid = encoded;
+        invalid["status"] = json!("0x2");
+        assert!(serde_json::from_value::<TransactionReceipt>(invalid).is_err());
+    }
 }
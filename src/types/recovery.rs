@@ -0,0 +1,62 @@
+use crate::prelude::*;
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecoveryError {
+    /// `r` or `s` is zero or out of the curve order.
+    InvalidSignature,
+    /// `v` or `yParity` does not encode a valid recovery id.
+    InvalidParity(u64),
+    /// No public key matches the signature.
+    Unrecoverable,
+    /// Chain id of a legacy transaction differs from the one its `v` was signed with.
+    ChainIdMismatch { expected: U64, got: Option<U64> },
+}
+
+impl std::fmt::Display for RecoveryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidSignature => write!(f, "invalid signature"),
+            Self::InvalidParity(v) => write!(f, "invalid signature parity {v}"),
+            Self::Unrecoverable => write!(f, "public key cannot be recovered"),
+            Self::ChainIdMismatch {
+                expected,
+                got: Some(got),
+            } => write!(f, "signed for chain {got} instead of {expected}"),
+            Self::ChainIdMismatch {
+                expected,
+                got: None,
+            } => write!(f, "signed without chain id instead of for chain {expected}"),
+        }
+    }
+}
+
+impl std::error::Error for RecoveryError {}
+
+/// Address corresponding to an uncompressed secp256k1 public key.
+pub(crate) fn public_key_address(key: &VerifyingKey) -> Address {
+    let point = key.to_encoded_point(false);
+    Address::from_slice(&keccak256(&point.as_bytes()[1..])[12..])
+}
+
+/// Recover the address that signed `hash` with signature `(r, s)` and parity `odd_y_parity`.
+///
+/// High `s` values, allowed before Homestead, are accepted.
+pub fn recover_address(
+    hash: H256,
+    r: H256,
+    s: H256,
+    odd_y_parity: bool,
+) -> Result<Address, RecoveryError> {
+    let mut signature =
+        Signature::from_scalars(r.0, s.0).map_err(|_| RecoveryError::InvalidSignature)?;
+    let mut recovery_id = RecoveryId::new(odd_y_parity, false);
+    if let Some(normalized) = signature.normalize_s() {
+        signature = normalized;
+        recovery_id = RecoveryId::new(!odd_y_parity, false);
+    }
+
+    let key = VerifyingKey::recover_from_prehash(hash.as_bytes(), &signature, recovery_id)
+        .map_err(|_| RecoveryError::Unrecoverable)?;
+    Ok(public_key_address(&key))
+}
@@ -0,0 +1,312 @@
+use crate::prelude::*;
+use ethnum::I256;
+use std::collections::HashMap;
+
+/// Selector of `Error(string)`.
+pub const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of `Panic(uint256)`.
+pub const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Selector of an error or function signature, e.g. `InsufficientBalance(uint256,uint256)`.
+pub fn abi_selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature);
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Description of a Solidity panic code.
+pub fn panic_description(code: U256) -> Option<&'static str> {
+    if code > 0xff {
+        return None;
+    }
+    Some(match code.as_u8() {
+        0x00 => "generic compiler panic",
+        0x01 => "assertion failed",
+        0x11 => "arithmetic underflow or overflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "invalid storage byte array encoding",
+        0x31 => "pop on empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "out of memory",
+        0x51 => "call to zero-initialized internal function",
+        _ => return None,
+    })
+}
+
+/// ABI-decoded argument of a custom error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AbiValue {
+    Address(Address),
+    Bool(bool),
+    Uint(U256),
+    Int(I256),
+    FixedBytes(Vec<u8>),
+    Bytes(Bytes),
+    String(String),
+}
+
+impl std::fmt::Display for AbiValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Address(v) => write!(f, "{v:?}"),
+            Self::Bool(v) => write!(f, "{v}"),
+            Self::Uint(v) => write!(f, "{v}"),
+            Self::Int(v) => write!(f, "{v}"),
+            Self::FixedBytes(v) => write!(f, "0x{}", hex::encode(v)),
+            Self::Bytes(v) => write!(f, "{v}"),
+            Self::String(v) => write!(f, "{v:?}"),
+        }
+    }
+}
+
+/// Decoded revert data of a failed call or transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RevertReason {
+    /// `Error(string)`, raised by `require` and `revert("...")`.
+    Error(String),
+    /// `Panic(uint256)`, raised by failed assertions and checked arithmetic.
+    Panic(U256),
+    /// Custom error found in the signature table, with its arguments if they could be decoded.
+    Custom {
+        signature: String,
+        args: Option<Vec<AbiValue>>,
+    },
+    /// Data that could not be decoded.
+    Unknown(Bytes),
+}
+
+impl RevertReason {
+    /// Decode `Error(string)` and `Panic(uint256)` revert data. Returns `None` for empty data,
+    /// i.e. a revert without reason.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        Self::decode_with(data, &HashMap::new())
+    }
+
+    /// Decode revert data, looking up custom errors by selector in `custom_errors`.
+    pub fn decode_with(data: &[u8], custom_errors: &HashMap<[u8; 4], String>) -> Option<Self> {
+        if data.is_empty() {
+            return None;
+        }
+        let unknown = || Self::Unknown(data.to_vec().into());
+        let Some((selector, args)) = data.split_first_chunk::<4>() else {
+            return Some(unknown());
+        };
+
+        Some(match *selector {
+            ERROR_SELECTOR => match decode_value("string", args, 0) {
+                Some(AbiValue::String(reason)) => Self::Error(reason),
+                _ => unknown(),
+            },
+            PANIC_SELECTOR => match decode_value("uint256", args, 0) {
+                Some(AbiValue::Uint(code)) => Self::Panic(code),
+                _ => unknown(),
+            },
+            selector => match custom_errors.get(&selector) {
+                Some(signature) => Self::Custom {
+                    signature: signature.clone(),
+                    args: decode_args(signature, args),
+                },
+                None => unknown(),
+            },
+        })
+    }
+}
+
+impl std::fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Error(reason) => write!(f, "reverted: {reason}"),
+            Self::Panic(code) => match panic_description(*code) {
+                Some(description) => write!(f, "panic: {description} (0x{code:02x})"),
+                None => write!(f, "panic: unknown code 0x{code:02x}"),
+            },
+            Self::Custom {
+                signature,
+                args: Some(args),
+            } => {
+                let name = &signature[..signature.find('(').unwrap_or(signature.len())];
+                let args = args.iter().map(ToString::to_string).collect::<Vec<_>>();
+                write!(f, "reverted: {name}({})", args.join(", "))
+            }
+            Self::Custom {
+                signature,
+                args: None,
+            } => write!(f, "reverted: {signature}"),
+            Self::Unknown(data) => write!(f, "reverted: {data}"),
+        }
+    }
+}
+
+/// Decode arguments of `signature`, if all of them are elementary types, `string` or `bytes`.
+fn decode_args(signature: &str, data: &[u8]) -> Option<Vec<AbiValue>> {
+    let params = signature.split_once('(')?.1.strip_suffix(')')?;
+    if params.is_empty() {
+        return Some(vec![]);
+    }
+    params
+        .split(',')
+        .enumerate()
+        .map(|(i, ty)| decode_value(ty.trim(), data, i * 32))
+        .collect()
+}
+
+fn word(data: &[u8], offset: usize) -> Option<&[u8; 32]> {
+    data.get(offset..offset.checked_add(32)?)?.try_into().ok()
+}
+
+fn word_to_usize(word: &[u8; 32]) -> Option<usize> {
+    let value = U256::from_be_bytes(*word);
+    (value <= usize::MAX as u128).then(|| value.as_usize())
+}
+
+/// Decode the value of type `ty` whose head is at `offset` in `data`.
+fn decode_value(ty: &str, data: &[u8], offset: usize) -> Option<AbiValue> {
+    let head = word(data, offset)?;
+    Some(match ty {
+        "address" => {
+            if head[..12].iter().any(|b| *b != 0) {
+                return None;
+            }
+            AbiValue::Address(Address::from_slice(&head[12..]))
+        }
+        "bool" => match U256::from_be_bytes(*head) {
+            v if v == 0 => AbiValue::Bool(false),
+            v if v == 1 => AbiValue::Bool(true),
+            _ => return None,
+        },
+        "string" | "bytes" => {
+            let start = word_to_usize(head)?;
+            let len = word_to_usize(word(data, start)?)?;
+            let start = start.checked_add(32)?;
+            let bytes = data.get(start..start.checked_add(len)?)?;
+            if ty == "string" {
+                AbiValue::String(String::from_utf8(bytes.to_vec()).ok()?)
+            } else {
+                AbiValue::Bytes(bytes.to_vec().into())
+            }
+        }
+        _ if ty.starts_with("uint") => {
+            check_int_size(&ty[4..])?;
+            AbiValue::Uint(U256::from_be_bytes(*head))
+        }
+        _ if ty.starts_with("int") => {
+            check_int_size(&ty[3..])?;
+            AbiValue::Int(I256::from_be_bytes(*head))
+        }
+        _ if ty.starts_with("bytes") => {
+            let size = ty[5..]
+                .parse::<usize>()
+                .ok()
+                .filter(|s| (1..=32).contains(s))?;
+            AbiValue::FixedBytes(head[..size].to_vec())
+        }
+        _ => return None,
+    })
+}
+
+fn check_int_size(size: &str) -> Option<()> {
+    if size.is_empty() {
+        return Some(());
+    }
+    let size = size.parse::<usize>().ok()?;
+    (size % 8 == 0 && (8..=256).contains(&size)).then_some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn decode_error_and_panic() {
+        let data = hex!(
+            "08c379a0"
+            "0000000000000000000000000000000000000000000000000000000000000020"
+            "0000000000000000000000000000000000000000000000000000000000000014"
+            "496e73756666696369656e742062616c616e6365000000000000000000000000"
+        );
+        let reason = RevertReason::decode(&data).unwrap();
+        assert_eq!(reason, RevertReason::Error("Insufficient balance".into()));
+        assert_eq!(reason.to_string(), "reverted: Insufficient balance");
+
+        let data = hex!(
+            "4e487b71"
+            "0000000000000000000000000000000000000000000000000000000000000011"
+        );
+        let reason = RevertReason::decode(&data).unwrap();
+        assert_eq!(reason, RevertReason::Panic(0x11.as_u256()));
+        assert_eq!(
+            reason.to_string(),
+            "panic: arithmetic underflow or overflow (0x11)"
+        );
+
+        assert_eq!(RevertReason::decode(&[]), None);
+        assert_eq!(
+            RevertReason::decode(&hex!("08c379a0")),
+            Some(RevertReason::Unknown(hex!("08c379a0").to_vec().into()))
+        );
+        assert_eq!(
+            RevertReason::decode(&hex!("deadbeef")).unwrap().to_string(),
+            "reverted: 0xdeadbeef"
+        );
+    }
+
+    #[test]
+    fn decode_custom_error() {
+        assert_eq!(abi_selector("Error(string)"), ERROR_SELECTOR);
+        assert_eq!(abi_selector("Panic(uint256)"), PANIC_SELECTOR);
+
+        let signature = "InsufficientBalance(address,uint256,string)";
+        let table = HashMap::from([
+            (abi_selector(signature), signature.to_string()),
+            (abi_selector("Unauthorized()"), "Unauthorized()".to_string()),
+            (
+                abi_selector("Weird((uint256,bool))"),
+                "Weird((uint256,bool))".to_string(),
+            ),
+        ]);
+
+        let mut data = abi_selector(signature).to_vec();
+        data.extend(hex!(
+            "000000000000000000000000deadbeef00000000000000000000000000000000"
+            "00000000000000000000000000000000000000000000000000000000000003e8"
+            "0000000000000000000000000000000000000000000000000000000000000060"
+            "0000000000000000000000000000000000000000000000000000000000000002"
+            "6869000000000000000000000000000000000000000000000000000000000000"
+        ));
+        let reason = RevertReason::decode_with(&data, &table).unwrap();
+        assert_eq!(
+            reason,
+            RevertReason::Custom {
+                signature: signature.into(),
+                args: Some(vec![
+                    AbiValue::Address(hex!("deadbeef00000000000000000000000000000000").into()),
+                    AbiValue::Uint(1000.as_u256()),
+                    AbiValue::String("hi".into()),
+                ]),
+            }
+        );
+        assert_eq!(
+            reason.to_string(),
+            "reverted: InsufficientBalance(0xdeadbeef00000000000000000000000000000000, 1000, \"hi\")"
+        );
+
+        assert_eq!(
+            RevertReason::decode_with(&abi_selector("Unauthorized()"), &table)
+                .unwrap()
+                .to_string(),
+            "reverted: Unauthorized()"
+        );
+        assert_eq!(
+            RevertReason::decode_with(&abi_selector("Weird((uint256,bool))"), &table),
+            Some(RevertReason::Custom {
+                signature: "Weird((uint256,bool))".into(),
+                args: None,
+            })
+        );
+        assert!(matches!(
+            RevertReason::decode(&data),
+            Some(RevertReason::Unknown(_))
+        ));
+    }
+}
@@ -0,0 +1,281 @@
+use crate::prelude::*;
+use k256::ecdsa::SigningKey;
+
+/// Secret key is zero or not below the curve order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidPrivateKey;
+
+impl std::fmt::Display for InvalidPrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid secp256k1 private key")
+    }
+}
+
+impl std::error::Error for InvalidPrivateKey {}
+
+/// Message that cannot be signed into a transaction for `eth_sendRawTransaction`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignerError {
+    /// EIP-4844 transactions are only accepted with their blob sidecar, which is not supported.
+    BlobTransaction,
+    /// EIP-155 `v` for the chain id does not fit in 64 bits.
+    ChainIdTooLarge(U64),
+}
+
+impl std::fmt::Display for SignerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BlobTransaction => write!(f, "signing blob transactions is not supported"),
+            Self::ChainIdTooLarge(chain_id) => write!(f, "chain id {chain_id} is too large"),
+        }
+    }
+}
+
+impl std::error::Error for SignerError {}
+
+/// Signs transactions offline with a secp256k1 private key.
+#[derive(Clone)]
+pub struct LocalSigner {
+    key: SigningKey,
+    address: Address,
+}
+
+impl std::fmt::Debug for LocalSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalSigner")
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+impl LocalSigner {
+    pub fn new(secret: H256) -> Result<Self, InvalidPrivateKey> {
+        let key = SigningKey::from_slice(secret.as_bytes()).map_err(|_| InvalidPrivateKey)?;
+        let address = public_key_address(key.verifying_key());
+        Ok(Self { key, address })
+    }
+
+    /// Address of the account controlled by the key.
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Sign `hash`, returning `r`, `s` and whether the y coordinate of `r` is odd. `s` is
+    /// normalised to the lower half of the curve order as required by EIP-2.
+    pub fn sign_hash(&self, hash: H256) -> (H256, H256, bool) {
+        let (signature, recovery_id) = self
+            .key
+            .sign_prehash_recoverable(hash.as_bytes())
+            .expect("32-byte prehash is always accepted");
+        let (r, s) = signature.split_bytes();
+        (
+            H256::from_slice(&r),
+            H256::from_slice(&s),
+            recovery_id.is_y_odd(),
+        )
+    }
+
+    /// Sign a fully populated message, returning the signed transaction and its EIP-2718
+    /// encoding for `eth_sendRawTransaction`.
+    ///
+    /// Legacy messages with a chain id are signed as per EIP-155, without one they are
+    /// replayable across chains. EIP-4844 messages are rejected, as nodes only accept them
+    /// together with their blob sidecar.
+    pub fn sign_transaction(
+        &self,
+        message: TransactionMessage,
+    ) -> Result<(Transaction, Bytes), SignerError> {
+        if let TransactionMessage::EIP4844 { .. } = message {
+            return Err(SignerError::BlobTransaction);
+        }
+
+        let (r, s, odd_y_parity) = self.sign_hash(message.signature_hash());
+        let parity = u64::from(odd_y_parity);
+        let (v, y_parity) = match &message {
+            TransactionMessage::Legacy {
+                chain_id: Some(chain_id),
+                ..
+            } => (
+                chain_id
+                    .as_u64()
+                    .checked_mul(2)
+                    .and_then(|v| v.checked_add(35 + parity))
+                    .ok_or(SignerError::ChainIdTooLarge(*chain_id))?,
+                None,
+            ),
+            TransactionMessage::Legacy { chain_id: None, .. } => (27 + parity, None),
+            _ => (parity, Some(parity.into())),
+        };
+        let signature = TransactionSignature { v: v.into(), r, s };
+        let raw = Bytes::from(message.encode_signed(&signature));
+
+        let transaction = Transaction {
+            message,
+            v: signature.v,
+            r,
+            s,
+            y_parity,
+            from: self.address,
+            hash: keccak256(&raw),
+            transaction_index: None,
+            block_number: None,
+            block_hash: None,
+        };
+        Ok((transaction, raw))
+    }
+
+    /// Sign an EIP-7702 authorization to delegate the account's code to `address`.
+    pub fn sign_authorization(
+        &self,
+        chain_id: U256,
+        address: Address,
+        nonce: U64,
+    ) -> SignedAuthorization {
+        let mut authorization = SignedAuthorization {
+            chain_id,
+            address,
+            nonce,
+            y_parity: U64::zero(),
+            r: U256::ZERO,
+            s: U256::ZERO,
+        };
+        let (r, s, odd_y_parity) = self.sign_hash(authorization.signature_hash());
+        authorization.y_parity = u64::from(odd_y_parity).into();
+        authorization.r = U256::from_be_bytes(r.0);
+        authorization.s = U256::from_be_bytes(s.0);
+        authorization
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    fn signer() -> LocalSigner {
+        LocalSigner::new(H256::repeat_byte(0x46)).unwrap()
+    }
+
+    #[test]
+    fn sign_eip155_example() {
+        // Example from EIP-155.
+        let message = TransactionMessage::Legacy {
+            chain_id: Some(1.into()),
+            nonce: 9.into(),
+            to: Some(Address::repeat_byte(0x35)),
+            gas: 21000.into(),
+            gas_price: U256::new(20_000_000_000),
+            value: U256::new(1_000_000_000_000_000_000),
+            input: Bytes::default(),
+        };
+        assert_eq!(
+            message.encode_for_signing(),
+            hex!("ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080")
+        );
+        assert_eq!(
+            message.signature_hash(),
+            H256(hex!(
+                "daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53"
+            ))
+        );
+
+        let signer = signer();
+        assert_eq!(
+            signer.address(),
+            hex!("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f").into()
+        );
+        let (transaction, raw) = signer.sign_transaction(message).unwrap();
+        assert_eq!(
+            raw.as_ref(),
+            hex!("f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83")
+        );
+        assert_eq!(transaction.v, 37.into());
+        assert_eq!(transaction.y_parity, None);
+        assert_eq!(transaction.from, signer.address());
+        assert_eq!(transaction.hash, transaction.compute_hash());
+        assert_eq!(transaction.encode(), raw);
+        assert_eq!(transaction.recover_from(), Ok(signer.address()));
+    }
+
+    #[test]
+    fn reject_unsignable_messages() {
+        let signer = signer();
+        let legacy = |chain_id: u64| TransactionMessage::Legacy {
+            chain_id: Some(chain_id.into()),
+            nonce: 0.into(),
+            to: None,
+            gas: 21000.into(),
+            gas_price: U256::ZERO,
+            value: U256::ZERO,
+            input: Bytes::default(),
+        };
+        let largest = (u64::MAX - 36) / 2;
+        assert!(signer.sign_transaction(legacy(largest)).is_ok());
+        assert_eq!(
+            signer.sign_transaction(legacy(u64::MAX / 2)).unwrap_err(),
+            SignerError::ChainIdTooLarge((u64::MAX / 2).into())
+        );
+        assert_eq!(
+            signer.sign_transaction(legacy(u64::MAX)).unwrap_err(),
+            SignerError::ChainIdTooLarge(u64::MAX.into())
+        );
+
+        let blob = TransactionMessage::EIP4844 {
+            chain_id: 1.into(),
+            nonce: 0.into(),
+            to: Address::zero(),
+            gas: 21000.into(),
+            max_fee_per_gas: U256::ZERO,
+            max_priority_fee_per_gas: U256::ZERO,
+            max_fee_per_blob_gas: U256::ONE,
+            value: U256::ZERO,
+            input: Bytes::default(),
+            access_list: vec![],
+            blob_versioned_hashes: vec![H256::repeat_byte(1)],
+        };
+        assert_eq!(
+            signer.sign_transaction(blob).unwrap_err(),
+            SignerError::BlobTransaction
+        );
+    }
+
+    #[test]
+    fn sign_typed_transaction() {
+        let signer = signer();
+        let authorization = signer.sign_authorization(
+            1.as_u256(),
+            hex!("63c0c19a282a1b52b07dd5a65b58948a07dae32b").into(),
+            7.into(),
+        );
+        assert_eq!(authorization.recover_authority(), Ok(signer.address()));
+
+        let message = TransactionMessage::EIP7702 {
+            chain_id: 1.into(),
+            nonce: 6.into(),
+            to: signer.address(),
+            gas: 100000.into(),
+            max_fee_per_gas: U256::new(20_000_000_000),
+            max_priority_fee_per_gas: U256::new(1_000_000_000),
+            value: U256::ZERO,
+            input: Bytes::default(),
+            access_list: vec![],
+            authorization_list: vec![authorization],
+        };
+        let (transaction, raw) = signer.sign_transaction(message).unwrap();
+        // Same transaction as signed by an independent implementation.
+        assert_eq!(
+            transaction.hash,
+            H256(hex!(
+                "b221a51ad59b5c6181d0028c5ac6f0d8a12e474768c4687f5f032c3d2e92c593"
+            ))
+        );
+        assert_eq!(raw.as_ref()[0], 4);
+        assert_eq!(transaction.y_parity, Some(transaction.v));
+        assert_eq!(transaction.recover_from(), Ok(signer.address()));
+
+        assert_eq!(
+            LocalSigner::new(H256::zero()).unwrap_err(),
+            InvalidPrivateKey
+        );
+    }
+}
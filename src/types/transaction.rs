@@ -1,7 +1,9 @@
+use super::encoding::u256_to_be_trimmed;
 use crate::prelude::*;
+use rlp::RlpStream;
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 
-/// Macro used by MessageCall types (LegacyType, EIP2930Type, EIP1155Type)
+/// Macro used by MessageCall types (LegacyType, EIP2930Type, EIP1559Type, EIP4844Type, EIP7702Type)
 ///
 /// It implements `Display` and `FromStr` to convert to/from the market to the string of its type.
 ///
@@ -49,6 +51,20 @@ impl EIP1559Type {
 }
 impl_display_and_from_str_for_type!(EIP1559Type);
 
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default, DeserializeFromStr, SerializeDisplay)]
+pub struct EIP4844Type;
+impl EIP4844Type {
+    const TYPE: &'static str = "0x03";
+}
+impl_display_and_from_str_for_type!(EIP4844Type);
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default, DeserializeFromStr, SerializeDisplay)]
+pub struct EIP7702Type;
+impl EIP7702Type {
+    const TYPE: &'static str = "0x04";
+}
+impl_display_and_from_str_for_type!(EIP7702Type);
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AccessListEntry {
@@ -56,6 +72,63 @@ pub struct AccessListEntry {
     pub storage_keys: Vec<H256>,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// EIP-7702 authorization to set the code of the signing account to that of `address`.
+pub struct SignedAuthorization {
+    /// Chain the authorization is valid on, zero for any chain.
+    pub chain_id: U256,
+    pub address: Address,
+    /// Nonce of the authority account.
+    pub nonce: U64,
+    pub y_parity: U64,
+    pub r: U256,
+    pub s: U256,
+}
+
+impl SignedAuthorization {
+    /// Prefix of the signed authorization payload.
+    pub const MAGIC: u8 = 0x05;
+
+    /// Half of the secp256k1 curve order, the largest `s` an authorization may carry.
+    #[cfg(feature = "recovery")]
+    const SECP256K1N_HALF: U256 = U256::from_words(
+        0x7fffffffffffffffffffffffffffffff,
+        0x5d576e7357a4501ddfe92f46681b20a0,
+    );
+
+    /// Hash signed by the authority: `keccak256(MAGIC || rlp([chain_id, address, nonce]))`.
+    pub fn signature_hash(&self) -> H256 {
+        let mut stream = RlpStream::new_list(3);
+        stream.append(&u256_to_be_trimmed(self.chain_id));
+        stream.append(&self.address);
+        stream.append(&self.nonce);
+
+        let mut payload = vec![Self::MAGIC];
+        payload.extend_from_slice(&stream.out());
+        keccak256(payload)
+    }
+
+    /// Recover the account that signed this authorization.
+    #[cfg(feature = "recovery")]
+    pub fn recover_authority(&self) -> Result<Address, RecoveryError> {
+        let odd_y_parity = match self.y_parity.as_u64() {
+            0 => false,
+            1 => true,
+            v => return Err(RecoveryError::InvalidParity(v)),
+        };
+        if self.s > Self::SECP256K1N_HALF {
+            return Err(RecoveryError::InvalidSignature);
+        }
+        recover_address(
+            self.signature_hash(),
+            H256(self.r.to_be_bytes()),
+            H256(self.s.to_be_bytes()),
+            odd_y_parity,
+        )
+    }
+}
+
 #[serde_as]
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 #[serde(untagged, deny_unknown_fields)]
@@ -117,10 +190,172 @@ pub enum MessageCall {
         #[serde(default, skip_serializing_if = "Option::is_none")]
         access_list: Option<Vec<AccessListEntry>>,
     },
+    #[serde(rename_all = "camelCase")]
+    EIP4844 {
+        #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+        tag: Option<EIP4844Type>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        from: Option<Address>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        to: Option<Address>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        gas: Option<U64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_fee_per_gas: Option<U256>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_priority_fee_per_gas: Option<U256>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_fee_per_blob_gas: Option<U256>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        value: Option<U256>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        data: Option<Bytes>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        access_list: Option<Vec<AccessListEntry>>,
+        blob_versioned_hashes: Vec<H256>,
+    },
+    #[serde(rename_all = "camelCase")]
+    EIP7702 {
+        #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+        tag: Option<EIP7702Type>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        from: Option<Address>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        to: Option<Address>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        gas: Option<U64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_fee_per_gas: Option<U256>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_priority_fee_per_gas: Option<U256>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        value: Option<U256>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        data: Option<Bytes>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        access_list: Option<Vec<AccessListEntry>>,
+        authorization_list: Vec<SignedAuthorization>,
+    },
+}
+
+impl MessageCall {
+    /// Attach an access list, e.g. one produced by `eth_createAccessList`.
+    ///
+    /// `Legacy` calls are upgraded to `EIP2930`, since they cannot carry an access list.
+    pub fn with_access_list(self, access_list: Vec<AccessListEntry>) -> Self {
+        match self {
+            MessageCall::Legacy {
+                tag,
+                from,
+                to,
+                gas,
+                gas_price,
+                value,
+                data,
+            } => MessageCall::EIP2930 {
+                tag: tag.map(|_| EIP2930Type),
+                from,
+                to,
+                gas,
+                gas_price,
+                value,
+                data,
+                access_list: Some(access_list),
+            },
+            MessageCall::EIP2930 {
+                tag,
+                from,
+                to,
+                gas,
+                gas_price,
+                value,
+                data,
+                ..
+            } => MessageCall::EIP2930 {
+                tag,
+                from,
+                to,
+                gas,
+                gas_price,
+                value,
+                data,
+                access_list: Some(access_list),
+            },
+            MessageCall::EIP1559 {
+                tag,
+                from,
+                to,
+                gas,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                value,
+                data,
+                ..
+            } => MessageCall::EIP1559 {
+                tag,
+                from,
+                to,
+                gas,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                value,
+                data,
+                access_list: Some(access_list),
+            },
+            MessageCall::EIP4844 {
+                tag,
+                from,
+                to,
+                gas,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                max_fee_per_blob_gas,
+                value,
+                data,
+                blob_versioned_hashes,
+                ..
+            } => MessageCall::EIP4844 {
+                tag,
+                from,
+                to,
+                gas,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                max_fee_per_blob_gas,
+                value,
+                data,
+                access_list: Some(access_list),
+                blob_versioned_hashes,
+            },
+            MessageCall::EIP7702 {
+                tag,
+                from,
+                to,
+                gas,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                value,
+                data,
+                authorization_list,
+                ..
+            } => MessageCall::EIP7702 {
+                tag,
+                from,
+                to,
+                gas,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                value,
+                data,
+                access_list: Some(access_list),
+                authorization_list,
+            },
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(tag = "type", deny_unknown_fields)]
+#[serde(tag = "type")]
 pub enum TransactionMessage {
     #[serde(rename = "0x0")]
     #[serde(rename_all = "camelCase")]
@@ -162,6 +397,35 @@ pub enum TransactionMessage {
         input: Bytes,
         access_list: Vec<AccessListEntry>,
     },
+    #[serde(rename = "0x3")]
+    #[serde(rename_all = "camelCase")]
+    EIP4844 {
+        chain_id: U64,
+        nonce: U64,
+        to: Address,
+        gas: U64,
+        max_fee_per_gas: U256,
+        max_priority_fee_per_gas: U256,
+        max_fee_per_blob_gas: U256,
+        value: U256,
+        input: Bytes,
+        access_list: Vec<AccessListEntry>,
+        blob_versioned_hashes: Vec<H256>,
+    },
+    #[serde(rename = "0x4")]
+    #[serde(rename_all = "camelCase")]
+    EIP7702 {
+        chain_id: U64,
+        nonce: U64,
+        to: Address,
+        gas: U64,
+        max_fee_per_gas: U256,
+        max_priority_fee_per_gas: U256,
+        value: U256,
+        input: Bytes,
+        access_list: Vec<AccessListEntry>,
+        authorization_list: Vec<SignedAuthorization>,
+    },
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -173,6 +437,9 @@ pub struct Transaction {
     pub v: U64,
     pub r: H256,
     pub s: H256,
+    /// Signature parity of typed transactions, duplicates `v`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub y_parity: Option<U64>,
 
     pub from: Address,
     pub hash: H256,
@@ -280,6 +547,309 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_message_call_ser() {
+        let legacy = MessageCall::Legacy {
+            tag: Some(LegacyType),
+            from: Some(Address::repeat_byte(0xAA)),
+            to: Some(hex!("727fc6a68321b754475c668a6abfb6e9e71c169a").into()),
+            gas: Some(21000_u64.into()),
+            gas_price: Some(20_000_000_000_u64.into()),
+            value: Some(1_000_000_000_u64.into()),
+            data: Some(hex!("a9059cbb").to_vec().into()),
+        };
+        let serialized = json!({
+            "type": "0x00",
+            "from": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "to": "0x727fc6a68321b754475c668a6abfb6e9e71c169a",
+            "gas": "0x5208",
+            "gasPrice": "0x4a817c800",
+            "value": "0x3b9aca00",
+            "data": "0xa9059cbb",
+        });
+        assert_eq!(serde_json::to_value(&legacy).unwrap(), serialized);
+        assert_eq!(
+            serde_json::from_value::<MessageCall>(serialized).unwrap(),
+            legacy
+        );
+
+        let eip2930 = MessageCall::EIP2930 {
+            tag: Some(EIP2930Type),
+            from: Some(Address::repeat_byte(0xAA)),
+            to: Some(hex!("727fc6a68321b754475c668a6abfb6e9e71c169a").into()),
+            gas: Some(21000_u64.into()),
+            gas_price: Some(20_000_000_000_u64.into()),
+            value: Some(1_000_000_000_u64.into()),
+            data: Some(hex!("a9059cbb").to_vec().into()),
+            access_list: Some(vec![AccessListEntry {
+                address: Address::repeat_byte(0xCC),
+                storage_keys: vec![H256::repeat_byte(0xDD)],
+            }]),
+        };
+        let serialized = json!({
+            "type": "0x01",
+            "from": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "to": "0x727fc6a68321b754475c668a6abfb6e9e71c169a",
+            "gas": "0x5208",
+            "gasPrice": "0x4a817c800",
+            "value": "0x3b9aca00",
+            "data": "0xa9059cbb",
+            "accessList": [
+                {
+                    "address": "0xcccccccccccccccccccccccccccccccccccccccc",
+                    "storageKeys": ["0xdddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddd"],
+                },
+            ],
+        });
+        assert_eq!(serde_json::to_value(&eip2930).unwrap(), serialized);
+        assert_eq!(
+            serde_json::from_value::<MessageCall>(serialized).unwrap(),
+            eip2930
+        );
+
+        let eip1559 = MessageCall::EIP1559 {
+            tag: Some(EIP1559Type),
+            from: Some(Address::repeat_byte(0xAA)),
+            to: None,
+            gas: Some(21000_u64.into()),
+            max_fee_per_gas: Some(30_000_000_000_u64.into()),
+            max_priority_fee_per_gas: Some(1_000_000_000_u64.into()),
+            value: None,
+            data: Some(hex!("6080604052").to_vec().into()),
+            access_list: Some(vec![]),
+        };
+        let serialized = json!({
+            "type": "0x02",
+            "from": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "gas": "0x5208",
+            "maxFeePerGas": "0x6fc23ac00",
+            "maxPriorityFeePerGas": "0x3b9aca00",
+            "data": "0x6080604052",
+            "accessList": [],
+        });
+        assert_eq!(serde_json::to_value(&eip1559).unwrap(), serialized);
+        assert_eq!(
+            serde_json::from_value::<MessageCall>(serialized).unwrap(),
+            eip1559
+        );
+    }
+
+    #[test]
+    fn test_message_call_blob() {
+        let call = MessageCall::EIP4844 {
+            tag: None,
+            from: None,
+            to: Some(Address::repeat_byte(0xAA)),
+            gas: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            max_fee_per_blob_gas: Some(3.as_u256()),
+            value: None,
+            data: None,
+            access_list: None,
+            blob_versioned_hashes: vec![H256::repeat_byte(0x01)],
+        };
+        let serialized = json!({
+            "to": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "maxFeePerBlobGas": "0x3",
+            "blobVersionedHashes": ["0x0101010101010101010101010101010101010101010101010101010101010101"],
+        });
+        assert_eq!(serde_json::to_value(&call).unwrap(), serialized);
+        assert_eq!(
+            serde_json::from_value::<MessageCall>(serialized).unwrap(),
+            call
+        );
+    }
+
+    #[test]
+    fn test_blob_tx_de() {
+        // Nodes also report effective `gasPrice` for typed transactions, which is not kept.
+        let serialized = json!({
+            "type": "0x3",
+            "chainId": "0x1",
+            "nonce": "0x2a",
+            "to": "0xc662c410c0ecf747543f5ba90660f6abebd9c8c4",
+            "gas": "0x5208",
+            "gasPrice": "0x3b9aca07",
+            "maxFeePerGas": "0x4a817c800",
+            "maxPriorityFeePerGas": "0x3b9aca00",
+            "maxFeePerBlobGas": "0x3b9aca00",
+            "value": "0x0",
+            "input": "0x",
+            "accessList": [],
+            "blobVersionedHashes": [
+                "0x01a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8",
+            ],
+            "v": "0x1",
+            "yParity": "0x1",
+            "r": "0xbe67e0a07db67da8d446f76add590e54b6e92cb6b8f9835aeb67540579a27717",
+            "s": "0x2d690516512020171c1ec870f6ff45398cc8609250326be89915fb538e7bd718",
+            "from": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "hash": "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+            "transactionIndex": "0x0",
+            "blockHash": "0xcccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccc",
+            "blockNumber": "0x12a05f2",
+        });
+        let tx = serde_json::from_value::<Tx>(serialized).unwrap();
+        let Tx::Transaction(tx) = tx else {
+            panic!("expected full transaction")
+        };
+        assert_eq!(tx.y_parity, Some(1.into()));
+        assert_eq!(
+            tx.message,
+            TransactionMessage::EIP4844 {
+                chain_id: 1.into(),
+                nonce: 0x2a.into(),
+                to: hex!("c662c410c0ecf747543f5ba90660f6abebd9c8c4").into(),
+                gas: 21000.into(),
+                max_fee_per_gas: 20_000_000_000_u64.into(),
+                max_priority_fee_per_gas: 1_000_000_000_u64.into(),
+                max_fee_per_blob_gas: 1_000_000_000_u64.into(),
+                value: 0.as_u256(),
+                input: Bytes::default(),
+                access_list: vec![],
+                blob_versioned_hashes: vec![hex!(
+                    "01a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8"
+                )
+                .into()],
+            }
+        );
+    }
+
+    fn authorization() -> SignedAuthorization {
+        SignedAuthorization {
+            chain_id: 1.as_u256(),
+            address: hex!("63c0c19a282a1b52b07dd5a65b58948a07dae32b").into(),
+            nonce: 7.into(),
+            y_parity: 0.into(),
+            r: U256::from_be_bytes(hex!(
+                "c78af71e7956d3503968218104b099526ab8fa09b90a5999bc6356cd29e5a638"
+            )),
+            s: U256::from_be_bytes(hex!(
+                "37bc90148184caead891d25fd9d174e5f75175426d3a4e06bc19dbaed4df63f7"
+            )),
+        }
+    }
+
+    #[test]
+    fn test_set_code_tx_ser() {
+        let message = TransactionMessage::EIP7702 {
+            chain_id: 1.into(),
+            nonce: 0.into(),
+            to: Address::repeat_byte(0xAA),
+            gas: 100_000.into(),
+            max_fee_per_gas: 20_000_000_000_u64.into(),
+            max_priority_fee_per_gas: 1_000_000_000_u64.into(),
+            value: 0.as_u256(),
+            input: Bytes::default(),
+            access_list: vec![],
+            authorization_list: vec![authorization()],
+        };
+        let serialized = json!({
+            "type": "0x4",
+            "chainId": "0x1",
+            "nonce": "0x0",
+            "to": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "gas": "0x186a0",
+            "maxFeePerGas": "0x4a817c800",
+            "maxPriorityFeePerGas": "0x3b9aca00",
+            "value": "0x0",
+            "input": "0x",
+            "accessList": [],
+            "authorizationList": [{
+                "chainId": "0x1",
+                "address": "0x63c0c19a282a1b52b07dd5a65b58948a07dae32b",
+                "nonce": "0x7",
+                "yParity": "0x0",
+                "r": "0xc78af71e7956d3503968218104b099526ab8fa09b90a5999bc6356cd29e5a638",
+                "s": "0x37bc90148184caead891d25fd9d174e5f75175426d3a4e06bc19dbaed4df63f7",
+            }],
+        });
+        assert_eq!(serde_json::to_value(&message).unwrap(), serialized);
+        assert_eq!(
+            serde_json::from_value::<TransactionMessage>(serialized).unwrap(),
+            message
+        );
+
+        let call = serde_json::from_value::<MessageCall>(json!({
+            "to": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "authorizationList": [authorization()],
+        }))
+        .unwrap();
+        assert!(matches!(call, MessageCall::EIP7702 { .. }));
+    }
+
+    #[cfg(feature = "recovery")]
+    #[test]
+    fn test_recover_authority() {
+        let auth = authorization();
+        assert_eq!(
+            auth.recover_authority().unwrap(),
+            Address::from(hex!("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"))
+        );
+
+        let mut invalid = auth.clone();
+        invalid.y_parity = 2.into();
+        assert_eq!(
+            invalid.recover_authority(),
+            Err(RecoveryError::InvalidParity(2))
+        );
+
+        let mut other = auth;
+        other.nonce = 8.into();
+        assert_ne!(
+            other.recover_authority().unwrap(),
+            Address::from(hex!("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"))
+        );
+    }
+
+    #[test]
+    fn test_with_access_list() {
+        let access_list = vec![AccessListEntry {
+            address: Address::repeat_byte(0xCC),
+            storage_keys: vec![H256::repeat_byte(0xDD)],
+        }];
+
+        let call = MessageCall::Legacy {
+            tag: Some(LegacyType),
+            from: None,
+            to: Some(Address::repeat_byte(0xAA)),
+            gas: Some(21000_u64.into()),
+            gas_price: Some(1.as_u256()),
+            value: None,
+            data: None,
+        };
+        assert_eq!(
+            call.with_access_list(access_list.clone()),
+            MessageCall::EIP2930 {
+                tag: Some(EIP2930Type),
+                from: None,
+                to: Some(Address::repeat_byte(0xAA)),
+                gas: Some(21000_u64.into()),
+                gas_price: Some(1.as_u256()),
+                value: None,
+                data: None,
+                access_list: Some(access_list.clone()),
+            }
+        );
+
+        let call = MessageCall::EIP1559 {
+            tag: None,
+            from: None,
+            to: None,
+            gas: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            value: None,
+            data: None,
+            access_list: Some(vec![]),
+        };
+        assert!(matches!(
+            call.with_access_list(access_list.clone()),
+            MessageCall::EIP1559 { access_list: Some(l), .. } if l == access_list
+        ));
+    }
+
     #[test]
     fn test_tx_ser() {
         let tx = Transaction {
@@ -295,6 +865,7 @@ mod tests {
             v: 40_u64.into(),
             r: hex!("be67e0a07db67da8d446f76add590e54b6e92cb6b8f9835aeb67540579a27717").into(),
             s: hex!("2d690516512020171c1ec870f6ff45398cc8609250326be89915fb538e7bd718").into(),
+            y_parity: None,
             from: Address::repeat_byte(0xAA),
             hash: H256::repeat_byte(0xBB),
             transaction_index: Some(0x42.into()),